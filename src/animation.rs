@@ -0,0 +1,171 @@
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, DynamicImage, Frame, RgbImage,
+};
+use std::{error::Error, fs::File, io::BufWriter, path::Path};
+
+/// Frame sequence settings. The scene is evaluated once per frame with `clock` defined.
+pub struct Animation {
+    /// Number of frames, or `None` to render a single image.
+    pub frames: Option<usize>,
+    pub clock_start: f64,
+    pub clock_end: f64,
+    /// Number of frames rendered in parallel.
+    pub jobs: usize,
+    /// Skip frames whose output file already exists.
+    pub resume: bool,
+    /// Display time of each frame of a GIF or APNG, in milliseconds.
+    pub frame_delay: u32,
+    /// Number of times a GIF or APNG is played, or 0 to loop forever.
+    pub loop_count: u16,
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        Self {
+            frames: None,
+            clock_start: 0.0,
+            clock_end: 1.0,
+            jobs: 1,
+            resume: false,
+            frame_delay: 100,
+            loop_count: 0,
+        }
+    }
+
+    /// Value of `clock` for the 0-based `frame`, going from `clock_start` to `clock_end` inclusive.
+    pub fn clock(&self, frame: usize) -> f64 {
+        match self.frames {
+            Some(frames) if frames > 1 => {
+                self.clock_start
+                    + (self.clock_end - self.clock_start) * frame as f64 / (frames - 1) as f64
+            }
+            _ => self.clock_start,
+        }
+    }
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Output path of the 1-based `frame`, e.g. `out.png` -> `out_0001.png`.
+pub fn frame_output_path(output: &str, frame: usize) -> String {
+    output_path_with_suffix(output, &format!("{:04}", frame))
+}
+
+/// `output` with `_<suffix>` appended to the file stem.
+pub(crate) fn output_path_with_suffix(output: &str, suffix: &str) -> String {
+    let path = Path::new(output);
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("frame");
+    let file_name = match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => format!("{}_{}.{}", stem, suffix, ext),
+        None => format!("{}_{}", stem, suffix),
+    };
+    path.with_file_name(file_name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Animated image format that holds every frame in a single file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Container {
+    Gif,
+    Apng,
+}
+
+impl Container {
+    /// Container selected by the extension of the output path, if any.
+    pub fn from_path(output: &str) -> Option<Self> {
+        let ext = Path::new(output).extension()?.to_str()?;
+        match ext.to_ascii_lowercase().as_str() {
+            "gif" => Some(Self::Gif),
+            "apng" => Some(Self::Apng),
+            _ => None,
+        }
+    }
+
+    /// Write `frames` to `output` as an animation.
+    pub fn save(
+        self,
+        output: &str,
+        frames: Vec<RgbImage>,
+        animation: &Animation,
+    ) -> Result<(), Box<dyn Error>> {
+        let Some(first) = frames.first() else {
+            return Err("No frames to save".into());
+        };
+        let dimensions = first.dimensions();
+        if frames.iter().any(|frame| frame.dimensions() != dimensions) {
+            return Err("All frames of an animation must have the same size".into());
+        }
+        let writer = BufWriter::new(File::create(output)?);
+        match self {
+            Self::Gif => save_gif(writer, frames, animation),
+            Self::Apng => save_apng(writer, frames, animation),
+        }
+    }
+}
+
+fn save_gif(
+    writer: BufWriter<File>,
+    frames: Vec<RgbImage>,
+    animation: &Animation,
+) -> Result<(), Box<dyn Error>> {
+    let mut encoder = GifEncoder::new_with_speed(writer, 10);
+    // a GIF is played once more than its repeat count
+    encoder.set_repeat(match animation.loop_count {
+        0 => Repeat::Infinite,
+        plays => Repeat::Finite(plays - 1),
+    })?;
+    let delay = Delay::from_numer_denom_ms(animation.frame_delay, 1);
+    encoder.encode_frames(frames.into_iter().map(|frame| {
+        Frame::from_parts(DynamicImage::ImageRgb8(frame).into_rgba8(), 0, 0, delay)
+    }))?;
+    Ok(())
+}
+
+/// APNG is written with the `png` crate directly, since the PNG encoder of `image` only writes
+/// still images; it is the version `image` already depends on.
+fn save_apng(
+    writer: BufWriter<File>,
+    frames: Vec<RgbImage>,
+    animation: &Animation,
+) -> Result<(), Box<dyn Error>> {
+    let (width, height) = frames[0].dimensions();
+    let mut encoder = png::Encoder::new(writer, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(frames.len() as u32, animation.loop_count as u32)?;
+    let (numerator, denominator) = apng_delay(animation.frame_delay)?;
+    encoder.set_frame_delay(numerator, denominator)?;
+    let mut writer = encoder.write_header()?;
+    for frame in frames.iter() {
+        writer.write_image_data(frame.as_raw())?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// The frame delay as the 16-bit fraction of a second of APNG: milliseconds when they fit,
+/// otherwise coarser units that still give the exact delay.
+fn apng_delay(ms: u32) -> Result<(u16, u16), Box<dyn Error>> {
+    [(1, 1000), (10, 100), (100, 10), (1000, 1)]
+        .into_iter()
+        .find_map(|(unit, denominator)| {
+            let numerator = u16::try_from(ms / unit).ok()?;
+            ms.is_multiple_of(unit).then_some((numerator, denominator))
+        })
+        .ok_or_else(|| {
+            format!(
+                "A frame delay of {} ms cannot be stored exactly in an APNG",
+                ms
+            )
+            .into()
+        })
+}
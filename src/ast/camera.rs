@@ -1,10 +1,28 @@
 use super::Expression;
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Projection {
+    Perspective,
+    Orthographic,
+    Fisheye,
+    Equirect,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct CameraConfig<'src> {
-    pub(crate) lookfrom: Expression<'src>,
-    pub(crate) lookat: Expression<'src>,
+    /// Required unless `auto_frame` is set.
+    pub(crate) lookfrom: Option<Expression<'src>>,
+    pub(crate) lookat: Option<Expression<'src>>,
+    pub(crate) auto_frame: bool,
+    pub(crate) direction: Option<Expression<'src>>,
     pub(crate) up: Option<Expression<'src>>,
     pub(crate) angle: Expression<'src>,
     pub(crate) dist_to_focus: Option<Expression<'src>>,
+    pub(crate) focus_on: Option<Expression<'src>>,
+    pub(crate) aperture: Option<Expression<'src>>,
+    pub(crate) f_stop: Option<Expression<'src>>,
+    pub(crate) shutter_open: Option<Expression<'src>>,
+    pub(crate) shutter_close: Option<Expression<'src>>,
+    pub(crate) projection: Projection,
+    pub(crate) ortho_width: Option<Expression<'src>>,
 }
@@ -7,4 +7,12 @@ pub struct Config<'src> {
     pub(crate) samples_per_pixel: Expression<'src>,
     pub(crate) max_depth: Option<Expression<'src>>,
     pub(crate) background: Option<Expression<'src>>,
+    pub(crate) fog: Option<Fog<'src>>,
+}
+
+/// A medium of constant density filling the whole scene.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Fog<'src> {
+    pub(crate) color: Expression<'src>,   // vec3
+    pub(crate) density: Expression<'src>, // number
 }
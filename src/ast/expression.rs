@@ -6,6 +6,7 @@ pub enum ExprEnum<'src> {
     NumLiteral(f64),
     StrLiteral(String),
     FnInvoke(Span<'src>, Vec<Expression<'src>>),
+    Defined(Span<'src>),
     Add(Box<Expression<'src>>, Box<Expression<'src>>),
     Sub(Box<Expression<'src>>, Box<Expression<'src>>),
     Mul(Box<Expression<'src>>, Box<Expression<'src>>),
@@ -24,6 +25,7 @@ pub enum ExprEnum<'src> {
         Box<Expression<'src>>,
         Box<Expression<'src>>,
     ),
+    Array(Vec<Expression<'src>>),
     Material(Box<Material<'src>>),
     Texture(Box<Texture<'src>>),
 }
@@ -17,6 +17,85 @@ pub struct Rotate<'src> {
 pub enum AffineProperties<'src> {
     Translation(Expression<'src>), // vec3
     Rotate(Rotate<'src>),
+    /// Rotation by `angle` degrees about `axis`.
+    AxisRotate {
+        axis: Expression<'src>,  // vec3
+        angle: Expression<'src>, // number
+    },
+    Scale(Expression<'src>),  // number or vec3
+    Matrix(Expression<'src>), // array of 3 or 4 rows of 4 numbers
+    /// Point that the rotations, scales and matrices after it are about.
+    Pivot(Expression<'src>), // vec3
+    Motion(Expression<'src>), // vec3
+}
+
+/// Part of the parallelogram spanned by the edges of a `Quad` that is filled.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum QuadShape {
+    Parallelogram,
+    /// The half with the corners `origin`, `origin + u` and `origin + v`.
+    Triangle,
+}
+
+/// A sphere of influence of a `Blob`, whose field falls from `strength` at `center` to 0 at
+/// `radius`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BlobComponent<'src> {
+    pub center: Expression<'src>,   // vec3
+    pub radius: Expression<'src>,   // number
+    pub strength: Expression<'src>, // number, negative to carve
+}
+
+/// How the points of a `Lathe` profile or a `Sweep` path are joined.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Interpolation {
+    Linear,
+    /// A Catmull-Rom curve through the points.
+    Cubic,
+}
+
+/// Cross-section of a `Sweep`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SweepProfile<'src> {
+    /// Closed polygon of `[x, y]` points in the plane perpendicular to the path.
+    Points(Expression<'src>), // array of [number, number]
+    /// Circle with `segments` points, making a tube.
+    Circle {
+        radius: Expression<'src>,           // number
+        segments: Option<Expression<'src>>, // number
+    },
+}
+
+/// Where the control points of a `BezierPatch` come from.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PatchSource<'src> {
+    /// The 16 points of one bicubic patch.
+    Control(Expression<'src>), // array of 16 vec3, or of 4 arrays of 4 vec3
+    /// Path of a BPT file of patches.
+    File(Expression<'src>), // string
+}
+
+/// Where the heights of a `HeightField` come from.
+#[derive(Debug, PartialEq, Clone)]
+pub enum HeightSource<'src> {
+    /// Path of an image whose brightness is the height.
+    Image(Expression<'src>), // string
+    /// Height at `x` and `z` from 0 to 1, sampled at `resolution` points along each.
+    Function {
+        function: Expression<'src>,           // number
+        resolution: Option<Expression<'src>>, // number
+    },
+}
+
+/// How the objects of a CSG object are combined, from the first one on.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CsgOperation {
+    /// The first object with the others cut out of it.
+    Difference,
+    /// The space inside all of the objects.
+    Intersection,
+    /// The space inside any of the objects, without the surfaces inside it.
+    Merge,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -29,6 +108,14 @@ pub enum Object<'src> {
         center: Expression<'src>,
         radius: Expression<'src>,
         material: Expression<'src>, // Expression::Material
+        center_end: Option<Expression<'src>>,
+        affine: Vec<AffineProperties<'src>>,
+    },
+    /// The surface where the summed field of `components` equals `threshold`.
+    Blob {
+        threshold: Expression<'src>, // number
+        components: Vec<BlobComponent<'src>>,
+        material: Expression<'src>, // Expression::Material
         affine: Vec<AffineProperties<'src>>,
     },
     Box {
@@ -41,4 +128,134 @@ pub enum Object<'src> {
         material: Expression<'src>, // Expression::Material
         affine: Vec<AffineProperties<'src>>,
     },
+    Quad {
+        origin: Expression<'src>, // vec3
+        u: Expression<'src>,      // vec3
+        v: Expression<'src>,      // vec3
+        shape: QuadShape,
+        material: Expression<'src>, // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    Triangle {
+        vertex: (Expression<'src>, Expression<'src>, Expression<'src>),
+        material: Expression<'src>, // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    Mesh {
+        vertices: Expression<'src>,        // array of vec3
+        faces: Expression<'src>,           // array of arrays of vertex indices
+        normals: Option<Expression<'src>>, // array of vec3, one per vertex
+        uvs: Option<Expression<'src>>,     // array of [u, v], one per vertex
+        material: Expression<'src>,        // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    Cylinder {
+        base: Expression<'src>,   // vec3
+        cap: Expression<'src>,    // vec3
+        radius: Expression<'src>, // number
+        /// Without the end disks.
+        open: bool,
+        material: Expression<'src>, // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    Cone {
+        base: Expression<'src>,        // vec3
+        base_radius: Expression<'src>, // number
+        cap: Expression<'src>,         // vec3
+        cap_radius: Expression<'src>,  // number
+        material: Expression<'src>,    // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    Disk {
+        center: Expression<'src>,   // vec3
+        normal: Expression<'src>,   // vec3
+        radius: Expression<'src>,   // number
+        material: Expression<'src>, // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    /// Centered at the origin, around the y axis.
+    Torus {
+        major: Expression<'src>,    // number
+        minor: Expression<'src>,    // number
+        material: Expression<'src>, // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    Model {
+        file: Expression<'src>,             // string
+        material: Option<Expression<'src>>, // Expression::Material, else from the file
+        /// Fit the model in a unit box centered at the origin.
+        normalize: bool,
+        affine: Vec<AffineProperties<'src>>,
+    },
+    Csg {
+        operation: CsgOperation,
+        objects: Vec<Object<'src>>,
+        affine: Vec<AffineProperties<'src>>,
+    },
+    /// A grid of heights over the box from the origin to `size`.
+    HeightField {
+        source: HeightSource<'src>,
+        size: Option<Expression<'src>>, // vec3
+        material: Expression<'src>,     // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    /// A profile of `[r, y]` points revolved around the y axis in `segments` steps.
+    Lathe {
+        points: Expression<'src>, // array of [number, number]
+        spline: Interpolation,
+        segments: Option<Expression<'src>>, // number
+        material: Expression<'src>,         // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    /// `profile` moved along the `path` of points, with both ends capped.
+    Sweep {
+        path: Expression<'src>, // array of vec3
+        profile: SweepProfile<'src>,
+        spline: Interpolation,
+        material: Expression<'src>, // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    /// Bézier patches split into `steps` × `steps` quads each.
+    BezierPatch {
+        source: PatchSource<'src>,
+        steps: Option<Expression<'src>>, // number
+        material: Expression<'src>,      // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    /// The surface where `function` of `x`, `y` and `z` equals `threshold`, found by ray
+    /// marching inside `bounds`.
+    Isosurface {
+        function: Expression<'src>,                   // number
+        bounds: (Expression<'src>, Expression<'src>), // vec3, the minimum and maximum corners
+        threshold: Option<Expression<'src>>,          // number
+        /// Limit of how fast `function` changes, 1 for signed distance functions.
+        max_gradient: Option<Expression<'src>>, // number
+        material: Expression<'src>,                   // Expression::Material
+        affine: Vec<AffineProperties<'src>>,
+    },
+    /// A `Prototype` placed by its own transforms, sharing the prototype's geometry.
+    Instance {
+        of: Expression<'src>,               // prototype
+        material: Option<Expression<'src>>, // Expression::Material, else the prototype's
+        affine: Vec<AffineProperties<'src>>,
+    },
+    /// A medium of constant density filling `boundary`.
+    Volume {
+        boundary: Box<Object<'src>>,
+        density: Expression<'src>,
+        color: Expression<'src>,         // vec3
+        noise: Option<Expression<'src>>, // number, scale of the Perlin noise varying the density
+        affine: Vec<AffineProperties<'src>>,
+    },
+}
+
+impl<'src> Object<'src> {
+    /// Objects built first and handed to this one, e.g. the members of `Objs`.
+    pub fn children(&self) -> &[Object<'src>] {
+        match self {
+            Object::Objects { objects, .. } | Object::Csg { objects, .. } => objects,
+            Object::Volume { boundary, .. } => std::slice::from_ref(boundary),
+            _ => &[],
+        }
+    }
 }
@@ -8,6 +8,12 @@ pub enum Statement<'src> {
         name: Span<'src>,
         ex: Expression<'src>,
     },
+    /// `name ?= ex;` assigns only when `name` is not defined yet.
+    VarDefault {
+        span: Span<'src>,
+        name: Span<'src>,
+        ex: Expression<'src>,
+    },
     If {
         span: Span<'src>,
         cond: Box<Expression<'src>>,
@@ -25,12 +31,38 @@ pub enum Statement<'src> {
         span: Span<'src>,
         object: Object<'src>,
     },
+    /// `Prototype name { ... }` builds the objects once, for any number of `Instance`s of
+    /// `name`.
+    Prototype {
+        span: Span<'src>,
+        name: Span<'src>,
+        objects: Vec<Object<'src>>,
+    },
     Camera {
         span: Span<'src>,
-        config: CameraConfig<'src>,
+        /// Name given with `Camera "name" { ... }`.
+        name: Option<Span<'src>>,
+        config: Box<CameraConfig<'src>>,
     },
     Config {
         span: Span<'src>,
         config: Config<'src>,
     },
 }
+
+impl<'src> Statement<'src> {
+    pub fn span(&self) -> Option<Span<'src>> {
+        match self {
+            Statement::Expression(expr) => Some(expr.span),
+            Statement::VarAssign { span, .. }
+            | Statement::VarDefault { span, .. }
+            | Statement::If { span, .. }
+            | Statement::While { span, .. }
+            | Statement::Object { span, .. }
+            | Statement::Prototype { span, .. }
+            | Statement::Camera { span, .. }
+            | Statement::Config { span, .. } => Some(*span),
+            Statement::Break | Statement::Continue => None,
+        }
+    }
+}
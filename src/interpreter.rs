@@ -1,11 +1,28 @@
+mod camera;
+pub use camera::Camera;
+mod compiler;
 mod expression;
+mod field;
 mod funcs;
+mod height_field;
 use funcs::standard_functions;
+mod limits;
+mod mesh;
+mod model;
+use limits::Budget;
+pub use limits::EvalLimits;
 mod object;
+mod patch;
+use object::{MOTION_END, MOTION_START};
+mod spline;
 mod statement;
+mod sweep;
+mod transform;
 use statement::eval_stmt;
 mod value;
-pub use value::ConfigValue;
+use value::CameraConfigValue;
+pub use value::{ConfigValue, DefineValue, FogValue};
+mod vm;
 
 use crate::ast::{Span, AST};
 
@@ -13,10 +30,12 @@ use crate::ast::{Span, AST};
 use pg_indicator::{PGOutput, PGStyle, ProgressBar};
 use rand::Rng;
 
-use ray_tracer_rs::{
-    camera::Camera,
-    hittable::{BvhNode, HittableEnum},
-    vec3::Color,
+use crate::tracer::{
+    aabb::AABB,
+    hittable::{BvhNode, ConstantMedium, Cuboid, Hittable, HittableEnum},
+    material::{Lambertian, MaterialEnum},
+    texture::{SolidColor, TextureEnum},
+    vec3::{Color, Vec3},
 };
 use std::{
     collections::HashMap,
@@ -26,6 +45,8 @@ use std::{
 };
 
 type Variables = HashMap<String, value::Value>;
+/// Cameras in the order they are declared, by name.
+type Cameras = Vec<(Option<String>, CameraConfigValue)>;
 
 const COLOR_MAX: f64 = 255.0;
 
@@ -35,11 +56,58 @@ pub struct EvalError<'a> {
     pub span: Option<Span<'a>>,
 }
 
-pub fn eval_ast<'a>(ast: &'a AST) -> Result<(HittableEnum, ConfigValue, Camera), EvalError<'a>> {
+#[derive(Debug, Clone, Default)]
+pub struct EvalOptions {
+    pub limits: EvalLimits,
+    /// Variables assigned before the scene is evaluated.
+    pub defines: Vec<(String, DefineValue)>,
+}
+
+impl EvalOptions {
+    fn predefined_variables(&self) -> Variables {
+        self.defines
+            .iter()
+            .map(|(name, value)| (name.clone(), value.into()))
+            .collect()
+    }
+}
+
+/// Evaluate the scene by compiling it to bytecode and running it on the VM.
+pub fn eval_ast<'a>(ast: &'a AST) -> Result<Scene, EvalError<'a>> {
+    eval_ast_with_options(ast, &EvalOptions::default())
+}
+
+pub fn eval_ast_with_options<'a>(
+    ast: &'a AST,
+    options: &EvalOptions,
+) -> Result<Scene, EvalError<'a>> {
     let mut world = Vec::new();
     let mut config = None;
-    let mut camera_config = None;
-    let mut variables = Variables::new();
+    let mut cameras = Cameras::new();
+    let mut budget = Budget::new(&options.limits);
+    let funcs = standard_functions();
+    let chunk = compiler::compile(ast, &funcs);
+    vm::run(
+        &chunk,
+        &funcs,
+        options.predefined_variables(),
+        &mut world,
+        &mut config,
+        &mut cameras,
+        &mut budget,
+    )?;
+    build_scene(world, config, cameras)
+}
+
+/// Evaluate the scene by walking the AST directly.
+///
+/// Produces the same scene as `eval_ast_with_options`; kept as a reference for the VM.
+pub fn eval_ast_tree_walk<'a>(ast: &'a AST, options: &EvalOptions) -> Result<Scene, EvalError<'a>> {
+    let mut world = Vec::new();
+    let mut config = None;
+    let mut cameras = Cameras::new();
+    let mut variables = options.predefined_variables();
+    let mut budget = Budget::new(&options.limits);
     let funcs = standard_functions();
     for stmt in ast.iter() {
         eval_stmt(
@@ -48,51 +116,154 @@ pub fn eval_ast<'a>(ast: &'a AST) -> Result<(HittableEnum, ConfigValue, Camera),
             &funcs,
             &mut world,
             &mut config,
-            &mut camera_config,
+            &mut cameras,
+            &mut budget,
         )?;
     }
-    // TODO: apply motion blur
-    let world = HittableEnum::BvhNode(Box::new(BvhNode::new(&mut world, 0.0, 0.0)));
+    build_scene(world, config, cameras)
+}
 
-    if config.is_none() {
+fn build_scene<'a>(
+    mut world: Vec<HittableEnum>,
+    config: Option<ConfigValue>,
+    mut cameras: Cameras,
+) -> Result<Scene, EvalError<'a>> {
+    let Some(config) = config else {
         return Err(EvalError {
             span: None,
             message: "Config not found".to_string(),
         });
-    }
-    if camera_config.is_none() {
+    };
+    if cameras.is_empty() {
         return Err(EvalError {
             span: None,
             message: "Camera not found".to_string(),
         });
     }
 
-    let config = config.unwrap();
-    let camera_config = camera_config.unwrap();
-
-    let camera = Camera::new(
-        camera_config.lookfrom,
-        camera_config.lookat,
-        camera_config.up,
-        camera_config.angle,
-        config.width / config.height,
-        0.0,
-        camera_config.dist_to_focus,
-        0.0,
-        1.0,
+    // the bounds cover every shutter interval, so all cameras share the BVH
+    let world = HittableEnum::BvhNode(Box::new(BvhNode::new(&mut world, MOTION_START, MOTION_END)));
+    if cameras
+        .iter()
+        .any(|(_, camera)| camera.auto_frame.is_some())
+    {
+        let Some(bounds) = world.bounding_box(MOTION_START, MOTION_END) else {
+            return Err(EvalError {
+                span: None,
+                message: "auto_frame needs an object with bounds in the scene".to_string(),
+            });
+        };
+        for (_, camera) in cameras.iter_mut() {
+            camera::frame(camera, &bounds, config.width / config.height);
+        }
+    }
+    // added after framing, since the fog is not part of what a camera frames
+    let world = match config.fog.as_ref() {
+        Some(fog) => {
+            let bounds = world.bounding_box(MOTION_START, MOTION_END);
+            let mut objects = vec![world, fog_medium(fog, bounds, &cameras)];
+            HittableEnum::BvhNode(Box::new(BvhNode::new(
+                &mut objects,
+                MOTION_START,
+                MOTION_END,
+            )))
+        }
+        None => world,
+    };
+    Ok(Scene {
+        world: Arc::new(world),
+        config,
+        cameras,
+    })
+}
+
+/// Margin of the fog around the objects and cameras, as a share of the size of the scene.
+const FOG_MARGIN: f64 = 0.1;
+
+/// Fog filling the box around `bounds` and the cameras. Rays leaving the box see the
+/// background, which an endless fog would hide.
+fn fog_medium(fog: &FogValue, bounds: Option<AABB>, cameras: &Cameras) -> HittableEnum {
+    let eye = cameras[0].1.lookfrom;
+    let (minimum, maximum) = cameras.iter().fold(
+        bounds.map_or((eye, eye), |bounds| (bounds.min(), bounds.max())),
+        |(minimum, maximum), (_, camera)| {
+            (minimum.min(&camera.lookfrom), maximum.max(&camera.lookfrom))
+        },
     );
-    Ok((world, config, camera))
+    let size = (maximum - minimum).length();
+    let margin = if size > 0.0 { size * FOG_MARGIN } else { 1.0 };
+    let margin = Vec3::new(margin, margin, margin);
+    // the boundary only bounds the medium, so its material is never seen
+    let boundary = HittableEnum::Cuboid(Cuboid::new(
+        &(minimum - margin),
+        &(maximum + margin),
+        MaterialEnum::Lambertian(Lambertian::new(&TextureEnum::SolidColor(SolidColor::new(
+            Color::zero(),
+        )))),
+    ));
+    HittableEnum::ConstantMedium(Box::new(ConstantMedium::new(
+        boundary,
+        fog.density,
+        fog.color,
+    )))
+}
+
+/// Evaluated scene, ready to be rendered from any of its cameras.
+pub struct Scene {
+    world: Arc<HittableEnum>,
+    pub config: ConfigValue,
+    cameras: Cameras,
+}
+
+impl Scene {
+    /// Names of the cameras in the order they are declared. The unnamed camera is `None`.
+    pub fn camera_names(&self) -> Vec<Option<&str>> {
+        self.cameras
+            .iter()
+            .map(|(name, _)| name.as_deref())
+            .collect()
+    }
+
+    /// The camera called `name`. Without a name, the unnamed camera or else the first one.
+    pub fn camera(&self, name: Option<&str>) -> Result<Camera, String> {
+        let camera = match name {
+            Some(name) => self
+                .cameras
+                .iter()
+                .find(|(n, _)| n.as_deref() == Some(name))
+                .ok_or_else(|| format!("Camera \"{}\" not found", name))?,
+            None => self
+                .cameras
+                .iter()
+                .find(|(n, _)| n.is_none())
+                .unwrap_or(&self.cameras[0]),
+        };
+        if camera.1.focus_on == Some(camera.1.lookfrom) {
+            return Err("focus_on must differ from the lookfrom found by auto_frame".to_string());
+        }
+        Ok(Camera::new(
+            &camera.1,
+            self.config.width / self.config.height,
+        ))
+    }
 }
 
 #[cfg(feature = "execution")]
-pub fn interpret(ast: &AST) -> (Vec<u8>, u32, u32) {
-    let res = eval_ast(ast);
+pub fn interpret(ast: &AST, options: &EvalOptions) -> (Vec<u8>, u32, u32) {
+    let res = eval_ast_with_options(ast, options);
     if res.is_err() {
         panic!("Error: {:?}", res.err().unwrap());
     }
-    let (world, config, camera) = res.unwrap();
-    let world = Arc::new(world);
+    let scene = res.unwrap();
+    let camera = scene.camera(None).unwrap();
+    render(&scene, camera)
+}
 
+/// Render `scene` seen from `camera` to an RGB buffer, returned with its width and height.
+#[cfg(feature = "execution")]
+pub fn render(scene: &Scene, camera: Camera) -> (Vec<u8>, u32, u32) {
+    let world = Arc::clone(&scene.world);
+    let config = &scene.config;
     let width = config.width.round() as u32;
     let height = config.height.round() as u32;
     let samples_per_pixel = config.samples_per_pixel.round() as usize;
@@ -122,8 +293,9 @@ pub fn interpret(ast: &AST) -> (Vec<u8>, u32, u32) {
                     for _ in 0..samples_per_pixel {
                         let u = (i as f64 + rng.gen_range(0.0..1.0)) / (width - 1) as f64;
                         let v = (j as f64 + rng.gen_range(0.0..1.0)) / (height - 1) as f64;
-                        let ray = camera.get_ray(u, v);
-                        pixel_color += ray.color(&background, &world, max_depth);
+                        if let Some(ray) = camera.get_ray(u, v) {
+                            pixel_color += ray.color(&background, &world, max_depth);
+                        }
                     }
                     let mut buf = buffer.write().unwrap();
                     let (r, g, b) = pixel_color.get_color(samples_per_pixel as i64);
@@ -149,3 +321,107 @@ pub fn interpret(ast: &AST) -> (Vec<u8>, u32, u32) {
         height,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser, tracer::ray::Ray};
+
+    const SCENE: &str = r#"
+        Config { width: 160, height: 90, samples_per_pixel: 1, background: <180, 200, 255>, }
+        Camera { lookfrom: <0, 6, 14>, lookat: <0, 1, 0>, angle: 50, }
+        Camera "side" { lookfrom: spline("catmull_rom", 0.25, [0, <14, 2, 0>], [1, <0, 2, 14>]), lookat: <0, 1, 0>, angle: 40, }
+
+        STEPS ?= 7;
+        corners = [<0, 0, 0>, <1, 0, 0>, <0.5, 1, 0>];
+        faces = [[0, 1, 2]];
+        Plane { vertex: (<-20, 0, -20>, <20, 0, 20>), material: Lambertian(Solid(<120, 120, 120>)), }
+
+        Prototype post {
+            Cylinder { base: <0, 0, 0>, cap: <0, 1, 0>, radius: 0.1, material: Lambertian(Solid(<200, 40, 40>)), }
+            Sphere { center: <0, 1.1, 0>, radius: 0.15, material: Metal(<220, 220, 220>, 0), }
+        }
+
+        i = 0;
+        while i < 100 {
+            i = i + 1;
+            if i > STEPS {
+                break;
+            }
+            if i == 3 {
+                continue;
+            }
+            j = 0;
+            while 1 {
+                j = j + 1;
+                if j > 2 {
+                    break;
+                }
+                x = i - 4;
+                z = j * 2 - 3;
+                if (i + j) / 2 == 2 {
+                    Box { vertex: (<x - 0.3, 0, z - 0.3>, <x + 0.3, 0.6, z + 0.3>), material: Lambertian(Solid(<200, 40, 40>)), rotateY: i * 10, }
+                    continue;
+                }
+                Sphere { center: <x, 0.5, z>, radius: 0.2 + j * 0.1, material: Lambertian(Solid(<20 * i, 100, 40 * j>)), }
+            }
+            Instance { of: post, translate: <i - 4, 0, 2>, }
+            Mesh { vertices: corners, faces: faces, material: Lambertian(Solid(<200, 40, 40>)), translate: <i - 4, 0, -3>, }
+        }
+        if defined(corners) && !defined(missing) {
+            Torus { major: 0.6, minor: 0.2, material: Lambertian(Solid(<200, 40, 40>)), translate: <0, 2.5, 0>, }
+        }
+    "#;
+
+    fn first_hit(world: &HittableEnum, ray: &Ray) -> Option<(f64, Vec3)> {
+        world
+            .hit(ray, 0.001, f64::INFINITY)
+            .map(|rec| (rec.t, rec.normal))
+    }
+
+    #[test]
+    fn vm_matches_tree_walker() {
+        let ast = parser::parse(SCENE).expect("the scene parses");
+        let options = EvalOptions::default();
+        let vm = eval_ast_with_options(&ast, &options).unwrap_or_else(|e| panic!("{}", e.message));
+        let walker = eval_ast_tree_walk(&ast, &options).unwrap_or_else(|e| panic!("{}", e.message));
+
+        assert_eq!(vm.config.width, walker.config.width);
+        assert_eq!(vm.config.height, walker.config.height);
+        assert_eq!(vm.config.background, walker.config.background);
+        assert_eq!(vm.camera_names(), walker.camera_names());
+        for ((_, a), (_, b)) in vm.cameras.iter().zip(walker.cameras.iter()) {
+            assert_eq!(a.lookfrom, b.lookfrom);
+            assert_eq!(a.lookat, b.lookat);
+            assert_eq!(a.angle, b.angle);
+        }
+        assert_eq!(
+            vm.world
+                .bounding_box(MOTION_START, MOTION_END)
+                .map(|b| (b.min(), b.max())),
+            walker
+                .world
+                .bounding_box(MOTION_START, MOTION_END)
+                .map(|b| (b.min(), b.max())),
+        );
+
+        let mut hits = 0;
+        for (_, camera) in vm.cameras.iter() {
+            for i in 0..40 {
+                for j in 0..40 {
+                    let target = Vec3::new(i as f64 * 0.25 - 5.0, j as f64 * 0.1, 0.0);
+                    let ray = Ray::new(camera.lookfrom, target - camera.lookfrom, 0.0);
+                    let hit = first_hit(&vm.world, &ray);
+                    assert_eq!(
+                        hit,
+                        first_hit(&walker.world, &ray),
+                        "ray towards {:?}",
+                        target
+                    );
+                    hits += usize::from(hit.is_some());
+                }
+            }
+        }
+        assert!(hits > 0);
+    }
+}
@@ -0,0 +1,145 @@
+use super::value::CameraConfigValue;
+use crate::ast::camera::Projection;
+use crate::tracer::{
+    aabb::AABB,
+    camera::Camera as LensCamera,
+    ray::Ray,
+    vec3::{cross, unit_vector, Point3, Vec3},
+};
+use rand::Rng;
+use std::f64::consts::PI;
+
+/// Camera generating the rays of every projection.
+///
+/// Perspective rays come from the ray tracer's thin-lens camera, so only they have depth of field.
+pub struct Camera {
+    lens: LensCamera,
+    projection: Projection,
+    origin: Point3,
+    // orthonormal basis: `u` points right, `v` up and `w` backwards from the view direction
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+    aspect_ratio: f64,
+    /// Field of view in radians, used by the fisheye projection.
+    fov: f64,
+    ortho_width: f64,
+    shutter_open: f64,
+    shutter_close: f64,
+}
+
+impl Camera {
+    pub(super) fn new(config: &CameraConfigValue, aspect_ratio: f64) -> Self {
+        let dist_to_focus = match (config.dist_to_focus, config.focus_on) {
+            (Some(dist), _) => dist,
+            (None, Some(point)) => (point - config.lookfrom).length(),
+            // a framed scene is in focus at its center
+            (None, None) if config.auto_frame.is_some() => {
+                (config.lookat - config.lookfrom).length()
+            }
+            (None, None) => 10.0,
+        };
+        // the image plane is at the focus distance, which therefore acts as the focal length
+        let aperture = config
+            .f_stop
+            .map_or(config.aperture, |f_stop| dist_to_focus / f_stop);
+        let lens = LensCamera::new(
+            config.lookfrom,
+            config.lookat,
+            config.up,
+            config.angle,
+            aspect_ratio,
+            aperture,
+            dist_to_focus,
+            config.shutter_open,
+            config.shutter_close,
+        );
+        let w = unit_vector(&(config.lookfrom - config.lookat));
+        let u = unit_vector(&cross(&config.up, &w));
+        let v = cross(&w, &u);
+        let fov = config.angle.to_radians();
+        // by default, as wide as the perspective view at the focus distance
+        let ortho_width = config
+            .ortho_width
+            .unwrap_or_else(|| 2.0 * dist_to_focus * (fov / 2.0).tan() * aspect_ratio);
+        Self {
+            lens,
+            projection: config.projection,
+            origin: config.lookfrom,
+            u,
+            v,
+            w,
+            aspect_ratio,
+            fov,
+            ortho_width,
+            shutter_open: config.shutter_open,
+            shutter_close: config.shutter_close,
+        }
+    }
+
+    /// Ray through the image position `(s, t)`, both in `[0, 1]` from the bottom left corner.
+    /// `None` when the position is outside of the image circle of a fisheye camera.
+    pub fn get_ray(&self, s: f64, t: f64) -> Option<Ray> {
+        let (origin, direction) = match self.projection {
+            Projection::Perspective => return Some(self.lens.get_ray(s, t)),
+            Projection::Orthographic => {
+                let height = self.ortho_width / self.aspect_ratio;
+                let origin = self.origin
+                    + self.u * ((s - 0.5) * self.ortho_width)
+                    + self.v * ((t - 0.5) * height);
+                (origin, -self.w)
+            }
+            Projection::Fisheye => {
+                // equidistant: the angle from the view direction grows linearly with the
+                // distance from the center, and the image circle fits the shorter side
+                let (x, y) = if self.aspect_ratio >= 1.0 {
+                    ((2.0 * s - 1.0) * self.aspect_ratio, 2.0 * t - 1.0)
+                } else {
+                    (2.0 * s - 1.0, (2.0 * t - 1.0) / self.aspect_ratio)
+                };
+                let r = (x * x + y * y).sqrt();
+                if r > 1.0 {
+                    return None;
+                }
+                let theta = r * self.fov / 2.0;
+                let phi = y.atan2(x);
+                let direction = self.u * (theta.sin() * phi.cos())
+                    + self.v * (theta.sin() * phi.sin())
+                    - self.w * theta.cos();
+                (self.origin, direction)
+            }
+            Projection::Equirect => {
+                let longitude = (s - 0.5) * 2.0 * PI;
+                let latitude = (t - 0.5) * PI;
+                let direction = self.u * (latitude.cos() * longitude.sin())
+                    + self.v * latitude.sin()
+                    - self.w * (latitude.cos() * longitude.cos());
+                (self.origin, direction)
+            }
+        };
+        let time = self.shutter_open
+            + (self.shutter_close - self.shutter_open) * rand::thread_rng().gen_range(0.0..1.0);
+        Some(Ray::new(origin, direction, time))
+    }
+}
+
+/// Sets `lookat` to the center of `bounds` and moves `lookfrom` back along the `auto_frame`
+/// direction until the sphere enclosing `bounds` fits in the view.
+pub(super) fn frame(config: &mut CameraConfigValue, bounds: &AABB, aspect_ratio: f64) {
+    let Some(direction) = config.auto_frame else {
+        return;
+    };
+    let center = (bounds.min() + bounds.max()) * 0.5;
+    let radius = (bounds.max() - bounds.min()).length() / 2.0;
+    let half_fov = config.angle.to_radians() / 2.0;
+    // half of the narrowest angle the view covers around its direction
+    let half_angle = match config.projection {
+        Projection::Perspective | Projection::Orthographic => {
+            half_fov.min((half_fov.tan() * aspect_ratio).atan())
+        }
+        Projection::Fisheye => half_fov.min(PI / 2.0),
+        Projection::Equirect => PI / 2.0,
+    };
+    config.lookat = center;
+    config.lookfrom = center - unit_vector(&direction) * (radius / half_angle.sin());
+}
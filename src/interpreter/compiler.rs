@@ -0,0 +1,366 @@
+use super::{
+    expression::{material_exprs, texture_exprs, BinOp},
+    funcs::Functions,
+    object::object_exprs,
+    statement::{camera_exprs, config_exprs},
+    value::Value,
+};
+use crate::ast::{
+    material::Material as MaterialAST, texture::Texture as TextureAST, CameraConfig, Config,
+    ExprEnum, Expression, Object, Span, Statement, AST,
+};
+use std::collections::HashMap;
+
+/// A single VM instruction.
+///
+/// Operands are popped from the value stack in the order they were pushed.
+pub(super) enum Op<'a> {
+    Const(usize),
+    /// Push the value of a variable slot, failing if it has never been assigned.
+    Load(usize),
+    /// Push whether a variable slot has been assigned.
+    Defined(usize),
+    Store(usize),
+    Pop,
+    Call(usize, usize), // function index, number of arguments
+    BinOp(BinOp),
+    /// Convert the top of the stack to a `Value::Bool`.
+    ToBool,
+    And,
+    Or,
+    Not,
+    Vec3,
+    // the `usize` of the following instructions is the number of values they pop
+    Array(usize),
+    Material(&'a MaterialAST<'a>, usize),
+    Texture(&'a TextureAST<'a>, usize),
+    Jump(usize),
+    JumpIfFalse(usize),
+    /// Count an executed statement against the evaluation limits.
+    Tick,
+    /// Reset the iteration counter of a loop.
+    LoopEnter(usize),
+    /// Count an iteration of a loop against the evaluation limits.
+    LoopIteration(usize),
+    /// Start collecting the children of an `Objs` group.
+    BeginGroup,
+    /// Object, number of values popped and the statement its build errors are reported at.
+    Object(&'a Object<'a>, usize, Option<Span<'a>>),
+    /// Turn the objects collected since `BeginGroup` into a prototype value.
+    Prototype,
+    Config(&'a Config<'a>, usize),
+    /// Camera name, properties and number of values popped.
+    Camera(Option<Span<'a>>, &'a CameraConfig<'a>, usize),
+    Fail(String),
+}
+
+/// Compiled program: instructions with their source spans, constants and resolved names.
+pub(super) struct Chunk<'a> {
+    pub(super) code: Vec<Op<'a>>,
+    pub(super) spans: Vec<Option<Span<'a>>>,
+    pub(super) constants: Vec<Value>,
+    /// Variable name of each slot, used for error messages.
+    pub(super) slot_names: Vec<String>,
+    pub(super) slot_indices: HashMap<String, usize>,
+    /// Function name of each function index.
+    pub(super) functions: Vec<String>,
+    pub(super) loop_count: usize,
+}
+
+struct LoopLabels<'a> {
+    start: usize,
+    breaks: Vec<usize>,
+    span: Span<'a>,
+}
+
+struct Compiler<'a, 'f> {
+    chunk: Chunk<'a>,
+    funcs: &'f Functions<'f>,
+    function_indices: HashMap<String, usize>,
+    loops: Vec<LoopLabels<'a>>,
+}
+
+pub(super) fn compile<'a>(ast: &'a AST, funcs: &Functions) -> Chunk<'a> {
+    let mut compiler = Compiler {
+        chunk: Chunk {
+            code: Vec::new(),
+            spans: Vec::new(),
+            constants: Vec::new(),
+            slot_names: Vec::new(),
+            slot_indices: HashMap::new(),
+            functions: Vec::new(),
+            loop_count: 0,
+        },
+        funcs,
+        function_indices: HashMap::new(),
+        loops: Vec::new(),
+    };
+    for stmt in ast.iter() {
+        compiler.stmt(stmt);
+    }
+    compiler.chunk
+}
+
+impl<'a, 'f> Compiler<'a, 'f> {
+    fn emit(&mut self, op: Op<'a>, span: Option<Span<'a>>) -> usize {
+        self.chunk.code.push(op);
+        self.chunk.spans.push(span);
+        self.chunk.code.len() - 1
+    }
+
+    fn patch_jump(&mut self, at: usize) {
+        let target = self.chunk.code.len();
+        match &mut self.chunk.code[at] {
+            Op::Jump(dest) | Op::JumpIfFalse(dest) => *dest = target,
+            _ => panic!("Only jump instructions can be patched"),
+        }
+    }
+
+    fn constant(&mut self, value: Value, span: Span<'a>) {
+        self.chunk.constants.push(value);
+        let index = self.chunk.constants.len() - 1;
+        self.emit(Op::Const(index), Some(span));
+    }
+
+    fn slot(&mut self, name: &str) -> usize {
+        if let Some(slot) = self.chunk.slot_indices.get(name) {
+            return *slot;
+        }
+        let slot = self.chunk.slot_names.len();
+        self.chunk.slot_names.push(name.to_string());
+        self.chunk.slot_indices.insert(name.to_string(), slot);
+        slot
+    }
+
+    fn stmts(&mut self, stmts: &'a AST) {
+        for stmt in stmts.iter() {
+            self.stmt(stmt);
+        }
+    }
+
+    /// Span to report a limit error at: the innermost enclosing loop, or `fallback` outside loops.
+    fn limit_span(&self, fallback: Option<Span<'a>>) -> Option<Span<'a>> {
+        self.loops.last().map(|labels| labels.span).or(fallback)
+    }
+
+    fn stmt(&mut self, stmt: &'a Statement) {
+        self.emit(Op::Tick, self.limit_span(stmt.span()));
+        match stmt {
+            Statement::Expression(expr) => {
+                self.expr(expr);
+                self.emit(Op::Pop, None);
+            }
+            Statement::VarAssign { name, ex, .. } => {
+                self.expr(ex);
+                let slot = self.slot(name.fragment());
+                self.emit(Op::Store(slot), Some(*name));
+            }
+            Statement::VarDefault { name, ex, .. } => {
+                let slot = self.slot(name.fragment());
+                self.emit(Op::Defined(slot), None);
+                self.emit(Op::Not, None);
+                let to_end = self.emit(Op::JumpIfFalse(0), None);
+                self.expr(ex);
+                self.emit(Op::Store(slot), Some(*name));
+                self.patch_jump(to_end);
+            }
+            Statement::If {
+                cond,
+                stmts,
+                else_stmts,
+                ..
+            } => {
+                self.expr(cond);
+                let to_else = self.emit(Op::JumpIfFalse(0), Some(cond.span));
+                self.stmts(stmts);
+                match else_stmts {
+                    Some(else_stmts) => {
+                        let to_end = self.emit(Op::Jump(0), None);
+                        self.patch_jump(to_else);
+                        self.stmts(else_stmts);
+                        self.patch_jump(to_end);
+                    }
+                    None => self.patch_jump(to_else),
+                }
+            }
+            Statement::While { span, cond, stmts } => {
+                let id = self.chunk.loop_count;
+                self.chunk.loop_count += 1;
+                self.emit(Op::LoopEnter(id), None);
+                let start = self.chunk.code.len();
+                self.expr(cond);
+                let to_end = self.emit(Op::JumpIfFalse(0), Some(cond.span));
+                self.emit(Op::LoopIteration(id), Some(*span));
+                self.loops.push(LoopLabels {
+                    start,
+                    breaks: vec![to_end],
+                    span: *span,
+                });
+                self.stmts(stmts);
+                self.emit(Op::Jump(start), None);
+                let labels = self.loops.pop().unwrap();
+                for at in labels.breaks {
+                    self.patch_jump(at);
+                }
+            }
+            // `break` and `continue` outside of a loop do nothing, as in the tree-walker
+            Statement::Break => {
+                if self.loops.is_empty() {
+                    return;
+                }
+                let at = self.emit(Op::Jump(0), None);
+                self.loops.last_mut().unwrap().breaks.push(at);
+            }
+            Statement::Continue => {
+                if let Some(labels) = self.loops.last() {
+                    let start = labels.start;
+                    self.emit(Op::Jump(start), None);
+                }
+            }
+            Statement::Object { span, object } => {
+                let limit_span = self.limit_span(Some(*span));
+                self.object(object, limit_span, *span);
+            }
+            Statement::Prototype {
+                span,
+                name,
+                objects,
+            } => {
+                let limit_span = self.limit_span(Some(*span));
+                self.emit(Op::BeginGroup, None);
+                for object in objects.iter() {
+                    self.object(object, limit_span, *span);
+                }
+                self.emit(Op::Prototype, None);
+                let slot = self.slot(name.fragment());
+                self.emit(Op::Store(slot), Some(*name));
+            }
+            Statement::Config { span, config } => {
+                let argc = self.exprs(config_exprs(config));
+                self.emit(Op::Config(config, argc), Some(*span));
+            }
+            Statement::Camera { span, name, config } => {
+                let argc = self.exprs(camera_exprs(config));
+                self.emit(Op::Camera(*name, config, argc), Some(*span));
+            }
+        }
+    }
+
+    /// `span` is where an exceeded object limit is reported, `statement` where other errors are.
+    fn object(&mut self, object: &'a Object, span: Option<Span<'a>>, statement: Span<'a>) {
+        let children = object.children();
+        if !children.is_empty() {
+            self.emit(Op::BeginGroup, None);
+            for obj in children.iter() {
+                self.object(obj, span, statement);
+            }
+        }
+        let argc = self.exprs(object_exprs(object));
+        self.emit(Op::Object(object, argc, Some(statement)), span);
+    }
+
+    fn expr(&mut self, expr: &'a Expression) {
+        match &expr.expr {
+            ExprEnum::Ident(ident) => {
+                if *ident.fragment() == "PI" {
+                    self.constant(Value::Num(std::f64::consts::PI), *ident);
+                    return;
+                }
+                let slot = self.slot(ident.fragment());
+                self.emit(Op::Load(slot), Some(*ident));
+            }
+            ExprEnum::Defined(name) => {
+                if *name.fragment() == "PI" {
+                    self.constant(Value::Bool(true), *name);
+                    return;
+                }
+                let slot = self.slot(name.fragment());
+                self.emit(Op::Defined(slot), Some(*name));
+            }
+            ExprEnum::NumLiteral(n) => self.constant(Value::Num(*n), expr.span),
+            ExprEnum::StrLiteral(s) => self.constant(Value::Str(s.as_str().into()), expr.span),
+            ExprEnum::FnInvoke(name, args) => {
+                let _name = name.fragment();
+                if !self.funcs.contains_key(*_name) {
+                    self.emit(
+                        Op::Fail(format!("function \"{}\" not found", name)),
+                        Some(*name),
+                    );
+                    return;
+                }
+                let index = match self.function_indices.get(*_name) {
+                    Some(index) => *index,
+                    None => {
+                        self.chunk.functions.push(_name.to_string());
+                        let index = self.chunk.functions.len() - 1;
+                        self.function_indices.insert(_name.to_string(), index);
+                        index
+                    }
+                };
+                for arg in args.iter() {
+                    self.expr(arg);
+                }
+                self.emit(Op::Call(index, args.len()), Some(*name));
+            }
+            ExprEnum::Add(a, b) => self.binary_op(BinOp::Add, a, b),
+            ExprEnum::Sub(a, b) => self.binary_op(BinOp::Sub, a, b),
+            ExprEnum::Mul(a, b) => self.binary_op(BinOp::Mul, a, b),
+            ExprEnum::Div(a, b) => self.binary_op(BinOp::Div, a, b),
+            ExprEnum::Gt(a, b) => self.binary_op(BinOp::Gt, a, b),
+            ExprEnum::Ge(a, b) => self.binary_op(BinOp::Ge, a, b),
+            ExprEnum::Lt(a, b) => self.binary_op(BinOp::Lt, a, b),
+            ExprEnum::Le(a, b) => self.binary_op(BinOp::Le, a, b),
+            ExprEnum::Eq(a, b) => self.binary_op(BinOp::Eq, a, b),
+            ExprEnum::Neq(a, b) => self.binary_op(BinOp::Neq, a, b),
+            // both operands are always evaluated, as in the tree-walker
+            ExprEnum::And(a, b) | ExprEnum::Or(a, b) => {
+                self.expr(a);
+                self.emit(Op::ToBool, Some(a.span));
+                self.expr(b);
+                self.emit(Op::ToBool, Some(b.span));
+                let op = match &expr.expr {
+                    ExprEnum::And(..) => Op::And,
+                    _ => Op::Or,
+                };
+                self.emit(op, None);
+            }
+            ExprEnum::Not(a) => {
+                self.expr(a);
+                self.emit(Op::ToBool, Some(a.span));
+                self.emit(Op::Not, None);
+            }
+            ExprEnum::Vec3(x, y, z) => {
+                self.expr(x);
+                self.expr(y);
+                self.expr(z);
+                self.emit(Op::Vec3, Some(x.span));
+            }
+            ExprEnum::Array(members) => {
+                let argc = self.exprs(members.iter().collect());
+                self.emit(Op::Array(argc), None);
+            }
+            ExprEnum::Material(mat) => {
+                let argc = self.exprs(material_exprs(mat));
+                self.emit(Op::Material(mat, argc), None);
+            }
+            ExprEnum::Texture(tex) => {
+                let argc = self.exprs(texture_exprs(tex));
+                self.emit(Op::Texture(tex, argc), None);
+            }
+        }
+    }
+
+    /// Compile expressions in order and return how many values they push.
+    fn exprs(&mut self, exprs: Vec<&'a Expression<'a>>) -> usize {
+        for expr in exprs.iter() {
+            self.expr(expr);
+        }
+        exprs.len()
+    }
+
+    fn binary_op(&mut self, op: BinOp, a: &'a Expression, b: &'a Expression) {
+        self.expr(a);
+        self.expr(b);
+        self.emit(Op::BinOp(op), Some(a.span));
+    }
+}
@@ -6,12 +6,215 @@ use super::{
 use crate::ast::{
     material::Material as MaterialAST, texture::Texture as TextureAST, ExprEnum, Expression,
 };
-use ray_tracer_rs::{
+use crate::tracer::{
     material::{Dielectric, DiffuseLight, Lambertian, MaterialEnum, Metal},
     texture::{Checker, NoiseTexture, SolidColor, TextureEnum},
     vec3::Color,
 };
 
+#[derive(Debug, Clone, Copy)]
+pub(super) enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Neq,
+}
+
+pub(super) fn binary_op(op: BinOp, lhs: Value, rhs: Value) -> Result<Value, String> {
+    let val = match (op, lhs, rhs) {
+        (BinOp::Add, Value::Num(lhs), Value::Num(rhs)) => Value::Num(lhs + rhs),
+        (BinOp::Add, Value::Vec3(x1, y1, z1), Value::Vec3(x2, y2, z2)) => {
+            Value::Vec3(x1 + x2, y1 + y2, z1 + z2)
+        }
+        (BinOp::Sub, Value::Num(lhs), Value::Num(rhs)) => Value::Num(lhs - rhs),
+        (BinOp::Sub, Value::Vec3(x1, y1, z1), Value::Vec3(x2, y2, z2)) => {
+            Value::Vec3(x1 - x2, y1 - y2, z1 - z2)
+        }
+        (BinOp::Mul, Value::Num(lhs), Value::Num(rhs)) => Value::Num(lhs * rhs),
+        (BinOp::Mul, Value::Num(lhs), Value::Vec3(x, y, z)) => {
+            Value::Vec3(lhs * x, lhs * y, lhs * z)
+        }
+        (BinOp::Mul, Value::Vec3(x, y, z), Value::Num(rhs)) => {
+            Value::Vec3(rhs * x, rhs * y, rhs * z)
+        }
+        (BinOp::Mul, Value::Vec3(x1, y1, z1), Value::Vec3(x2, y2, z2)) => {
+            Value::Vec3(x1 * x2, y1 * y2, z1 * z2)
+        }
+        (BinOp::Div, Value::Num(lhs), Value::Num(rhs)) => Value::Num(lhs / rhs),
+        (BinOp::Div, Value::Vec3(x, y, z), Value::Num(rhs)) => {
+            Value::Vec3(x / rhs, y / rhs, z / rhs)
+        }
+        (BinOp::Gt, Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs > rhs),
+        (BinOp::Ge, Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs >= rhs),
+        (BinOp::Lt, Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs < rhs),
+        (BinOp::Le, Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs <= rhs),
+        (BinOp::Eq, Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs == rhs),
+        (BinOp::Neq, Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs != rhs),
+        (op, _, _) => {
+            let symbol = match op {
+                BinOp::Add => "+",
+                BinOp::Sub => "-",
+                BinOp::Mul => "*",
+                BinOp::Div => "/",
+                BinOp::Gt => ">",
+                BinOp::Ge => ">=",
+                BinOp::Lt => "<",
+                BinOp::Le => "<=",
+                BinOp::Eq => "==",
+                BinOp::Neq => "!=",
+            };
+            return Err(format!("Invalid operands for {}", symbol));
+        }
+    };
+    Ok(val)
+}
+
+pub(super) fn call_fn(func: &FnDecl, args: &[Value]) -> Result<Value, String> {
+    // TODO: handle non-native functions
+    match func {
+        FnDecl::Native(native) => native.code.as_ref()(args),
+    }
+}
+
+/// Sub-expressions of a material, in the order `build_material` consumes their values.
+pub(super) fn material_exprs<'a>(mat: &'a MaterialAST<'a>) -> Vec<&'a Expression<'a>> {
+    match mat {
+        MaterialAST::Lambertian { texture } => vec![texture],
+        MaterialAST::Metal { color, fuzz } => vec![color, fuzz],
+        MaterialAST::Dielectric { reflection_index } => vec![reflection_index],
+        MaterialAST::Light { color, intensity } => vec![color, intensity],
+    }
+}
+
+pub(super) fn build_material<'a>(
+    mat: &'a MaterialAST<'a>,
+    args: Vec<Value>,
+) -> Result<Value, EvalError<'a>> {
+    let mut args = args.into_iter();
+    let val = match mat {
+        MaterialAST::Lambertian { texture } => match next_arg(&mut args) {
+            Value::Texture(texture) => {
+                Value::Material(MaterialEnum::Lambertian(Lambertian::new(&texture)))
+            }
+            _ => {
+                return Err(EvalError {
+                    span: Some(texture.span),
+                    message: "Invalid texture type".to_string(),
+                })
+            }
+        },
+        MaterialAST::Metal { color, .. } => match (next_arg(&mut args), next_arg(&mut args)) {
+            (Value::Vec3(r, g, b), Value::Num(fuzz)) => Value::Material(MaterialEnum::Metal(
+                Metal::new(&(Color::new(r, g, b) / COLOR_MAX), fuzz),
+            )),
+            _ => {
+                return Err(EvalError {
+                    span: Some(color.span),
+                    message: "Invalid texture type".to_string(),
+                })
+            }
+        },
+        MaterialAST::Dielectric { reflection_index } => match next_arg(&mut args) {
+            Value::Num(reflection_index) => {
+                Value::Material(MaterialEnum::Dielectric(Dielectric::new(reflection_index)))
+            }
+            _ => {
+                return Err(EvalError {
+                    span: Some(reflection_index.span),
+                    message: "Invalid reflection index type".to_string(),
+                })
+            }
+        },
+        MaterialAST::Light { color, .. } => match (next_arg(&mut args), next_arg(&mut args)) {
+            (Value::Vec3(r, g, b), Value::Num(intensity)) => Value::Material(
+                MaterialEnum::DiffuseLight(DiffuseLight::new(&TextureEnum::SolidColor(
+                    SolidColor::new(Color::new(r, g, b) / COLOR_MAX * intensity),
+                ))),
+            ),
+            _ => {
+                return Err(EvalError {
+                    span: Some(color.span),
+                    message: "Invalid color or intensity type".to_string(),
+                })
+            }
+        },
+    };
+    Ok(val)
+}
+
+/// Sub-expressions of a texture, in the order `build_texture` consumes their values.
+pub(super) fn texture_exprs<'a>(tex: &'a TextureAST<'a>) -> Vec<&'a Expression<'a>> {
+    match tex {
+        TextureAST::SolidColor(color) => vec![color],
+        TextureAST::Checker(odd, even) => vec![odd, even],
+        TextureAST::Perlin(scale) => vec![scale],
+    }
+}
+
+pub(super) fn build_texture<'a>(
+    tex: &'a TextureAST<'a>,
+    args: Vec<Value>,
+) -> Result<Value, EvalError<'a>> {
+    let mut args = args.into_iter();
+    let val = match tex {
+        TextureAST::SolidColor(color) => match next_arg(&mut args) {
+            Value::Vec3(r, g, b) => Value::Texture(TextureEnum::SolidColor(SolidColor::new(
+                Color::new(r, g, b) / COLOR_MAX,
+            ))),
+            _ => {
+                return Err(EvalError {
+                    span: Some(color.span),
+                    message: "Invalid color type".to_string(),
+                })
+            }
+        },
+        TextureAST::Checker(odd, _) => match (next_arg(&mut args), next_arg(&mut args)) {
+            (Value::Texture(odd), Value::Texture(even)) => {
+                Value::Texture(TextureEnum::Checker(Checker::new(odd, even)))
+            }
+            _ => {
+                return Err(EvalError {
+                    span: Some(odd.span),
+                    message: "Invalid checker type".to_string(),
+                })
+            }
+        },
+        TextureAST::Perlin(scale) => match next_arg(&mut args) {
+            Value::Num(scale) => {
+                Value::Texture(TextureEnum::NoiseTexture(NoiseTexture::new(scale)))
+            }
+            _ => {
+                return Err(EvalError {
+                    span: Some(scale.span),
+                    message: "Invalid scale type".to_string(),
+                })
+            }
+        },
+    };
+    Ok(val)
+}
+
+pub(super) fn next_arg(args: &mut impl Iterator<Item = Value>) -> Value {
+    args.next().expect("missing evaluated argument")
+}
+
+pub(super) fn eval_exprs<'a>(
+    exprs: Vec<&'a Expression<'a>>,
+    variables: &mut Variables,
+    funcs: &Functions,
+) -> Result<Vec<Value>, EvalError<'a>> {
+    exprs
+        .into_iter()
+        .map(|expr| eval_expr(expr, variables, funcs))
+        .collect()
+}
+
 pub(super) fn eval_expr<'a>(
     ast: &'a Expression,
     variables: &mut Variables,
@@ -33,8 +236,12 @@ pub(super) fn eval_expr<'a>(
             }
             val.unwrap().clone()
         }
+        ExprEnum::Defined(name) => {
+            let _name = name.fragment();
+            Value::Bool(*_name == "PI" || variables.contains_key(*_name))
+        }
         ExprEnum::NumLiteral(n) => Value::Num(*n),
-        ExprEnum::StrLiteral(s) => Value::Str(s.clone()),
+        ExprEnum::StrLiteral(s) => Value::Str(s.as_str().into()),
         ExprEnum::FnInvoke(name, args) => {
             let _name = name.fragment();
             let func = funcs.get(*_name);
@@ -44,94 +251,20 @@ pub(super) fn eval_expr<'a>(
                     message: format!("function \"{}\" not found", name),
                 });
             }
-            let args = args
-                .iter()
-                .map(|arg| eval_expr(arg, variables, funcs))
-                .collect::<Vec<_>>();
-            for arg in &args {
-                if let Err(e) = arg {
-                    return Err(e.clone());
-                }
-            }
-            let args = args.into_iter().map(|arg| arg.unwrap()).collect::<Vec<_>>();
-            // TODO: handle non-native functions
-            match func.unwrap() {
-                FnDecl::Native(native) => {
-                    let ans = native.code.as_ref()(&args);
-                    if let Err(e) = ans {
-                        return Err(EvalError {
-                            span: Some(*name),
-                            message: e,
-                        });
-                    }
-                    ans.unwrap()
-                }
-            }
-        }
-        ExprEnum::Add(a, b) => {
-            let lhs = eval_expr(a, variables, funcs)?;
-            let rhs = eval_expr(b, variables, funcs)?;
-            match (lhs, rhs) {
-                (Value::Num(lhs), Value::Num(rhs)) => Value::Num(lhs + rhs),
-                (Value::Vec3(x1, y1, z1), Value::Vec3(x2, y2, z2)) => {
-                    Value::Vec3(x1 + x2, y1 + y2, z1 + z2)
-                }
-                _ => {
-                    return Err(EvalError {
-                        span: Some(a.span),
-                        message: "Invalid operands for +".to_string(),
-                    })
-                }
-            }
-        }
-        ExprEnum::Sub(a, b) => {
-            let lhs = eval_expr(a, variables, funcs)?;
-            let rhs = eval_expr(b, variables, funcs)?;
-            match (lhs, rhs) {
-                (Value::Num(lhs), Value::Num(rhs)) => Value::Num(lhs - rhs),
-                (Value::Vec3(x1, y1, z1), Value::Vec3(x2, y2, z2)) => {
-                    Value::Vec3(x1 - x2, y1 - y2, z1 - z2)
-                }
-                _ => {
-                    return Err(EvalError {
-                        span: Some(a.span),
-                        message: "Invalid operands for -".to_string(),
-                    })
-                }
-            }
-        }
-        ExprEnum::Mul(a, b) => {
-            let lhs = eval_expr(a, variables, funcs)?;
-            let rhs = eval_expr(b, variables, funcs)?;
-            match (lhs, rhs) {
-                (Value::Num(lhs), Value::Num(rhs)) => Value::Num(lhs * rhs),
-                (Value::Num(lhs), Value::Vec3(x, y, z)) => Value::Vec3(lhs * x, lhs * y, lhs * z),
-                (Value::Vec3(x, y, z), Value::Num(rhs)) => Value::Vec3(rhs * x, rhs * y, rhs * z),
-                (Value::Vec3(x1, y1, z1), Value::Vec3(x2, y2, z2)) => {
-                    Value::Vec3(x1 * x2, y1 * y2, z1 * z2)
-                }
-                _ => {
-                    return Err(EvalError {
-                        span: Some(a.span),
-                        message: "Invalid operands for *".to_string(),
-                    })
-                }
-            }
-        }
-        ExprEnum::Div(a, b) => {
-            let lhs = eval_expr(a, variables, funcs)?;
-            let rhs = eval_expr(b, variables, funcs)?;
-            match (lhs, rhs) {
-                (Value::Num(lhs), Value::Num(rhs)) => Value::Num(lhs / rhs),
-                (Value::Vec3(x, y, z), Value::Num(rhs)) => Value::Vec3(x / rhs, y / rhs, z / rhs),
-                _ => {
-                    return Err(EvalError {
-                        span: Some(a.span),
-                        message: "Invalid operands for /".to_string(),
-                    })
-                }
+            let args = eval_exprs(args.iter().collect(), variables, funcs)?;
+            let ans = call_fn(func.unwrap(), &args);
+            if let Err(e) = ans {
+                return Err(EvalError {
+                    span: Some(*name),
+                    message: e,
+                });
             }
+            ans.unwrap()
         }
+        ExprEnum::Add(a, b) => eval_binary_op(BinOp::Add, a, b, variables, funcs)?,
+        ExprEnum::Sub(a, b) => eval_binary_op(BinOp::Sub, a, b, variables, funcs)?,
+        ExprEnum::Mul(a, b) => eval_binary_op(BinOp::Mul, a, b, variables, funcs)?,
+        ExprEnum::Div(a, b) => eval_binary_op(BinOp::Div, a, b, variables, funcs)?,
         ExprEnum::And(a, b) => {
             let lhs = eval_expr(a, variables, funcs)?;
             let rhs = eval_expr(b, variables, funcs)?;
@@ -170,84 +303,12 @@ pub(super) fn eval_expr<'a>(
             }
             Value::Bool(lhs_bool.unwrap() || rhs_bool.unwrap())
         }
-        ExprEnum::Gt(a, b) => {
-            let lhs = eval_expr(a, variables, funcs)?;
-            let rhs = eval_expr(b, variables, funcs)?;
-            match (lhs, rhs) {
-                (Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs > rhs),
-                _ => {
-                    return Err(EvalError {
-                        span: Some(a.span),
-                        message: "Invalid operands for >".to_string(),
-                    })
-                }
-            }
-        }
-        ExprEnum::Ge(a, b) => {
-            let lhs = eval_expr(a, variables, funcs)?;
-            let rhs = eval_expr(b, variables, funcs)?;
-            match (lhs, rhs) {
-                (Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs >= rhs),
-                _ => {
-                    return Err(EvalError {
-                        span: Some(a.span),
-                        message: "Invalid operands for >=".to_string(),
-                    })
-                }
-            }
-        }
-        ExprEnum::Lt(a, b) => {
-            let lhs = eval_expr(a, variables, funcs)?;
-            let rhs = eval_expr(b, variables, funcs)?;
-            match (lhs, rhs) {
-                (Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs < rhs),
-                _ => {
-                    return Err(EvalError {
-                        span: Some(a.span),
-                        message: "Invalid operands for <".to_string(),
-                    })
-                }
-            }
-        }
-        ExprEnum::Le(a, b) => {
-            let lhs = eval_expr(a, variables, funcs)?;
-            let rhs = eval_expr(b, variables, funcs)?;
-            match (lhs, rhs) {
-                (Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs <= rhs),
-                _ => {
-                    return Err(EvalError {
-                        span: Some(a.span),
-                        message: "Invalid operands for <=".to_string(),
-                    })
-                }
-            }
-        }
-        ExprEnum::Eq(a, b) => {
-            let lhs = eval_expr(a, variables, funcs)?;
-            let rhs = eval_expr(b, variables, funcs)?;
-            match (lhs, rhs) {
-                (Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs == rhs),
-                _ => {
-                    return Err(EvalError {
-                        span: Some(a.span),
-                        message: "Invalid operands for ==".to_string(),
-                    })
-                }
-            }
-        }
-        ExprEnum::Neq(a, b) => {
-            let lhs = eval_expr(a, variables, funcs)?;
-            let rhs = eval_expr(b, variables, funcs)?;
-            match (lhs, rhs) {
-                (Value::Num(lhs), Value::Num(rhs)) => Value::Bool(lhs != rhs),
-                _ => {
-                    return Err(EvalError {
-                        span: Some(a.span),
-                        message: "Invalid operands for !=".to_string(),
-                    })
-                }
-            }
-        }
+        ExprEnum::Gt(a, b) => eval_binary_op(BinOp::Gt, a, b, variables, funcs)?,
+        ExprEnum::Ge(a, b) => eval_binary_op(BinOp::Ge, a, b, variables, funcs)?,
+        ExprEnum::Lt(a, b) => eval_binary_op(BinOp::Lt, a, b, variables, funcs)?,
+        ExprEnum::Le(a, b) => eval_binary_op(BinOp::Le, a, b, variables, funcs)?,
+        ExprEnum::Eq(a, b) => eval_binary_op(BinOp::Eq, a, b, variables, funcs)?,
+        ExprEnum::Neq(a, b) => eval_binary_op(BinOp::Neq, a, b, variables, funcs)?,
         ExprEnum::Not(a) => {
             let val = eval_expr(a, variables, funcs)?.to_bool();
             if let Err(e) = val {
@@ -272,113 +333,32 @@ pub(super) fn eval_expr<'a>(
                 }
             }
         }
-        ExprEnum::Material(mat) => match mat.as_ref() {
-            MaterialAST::Lambertian { texture } => {
-                let texture_val = eval_expr(texture, variables, funcs)?;
-                match texture_val {
-                    Value::Texture(texture) => {
-                        Value::Material(MaterialEnum::Lambertian(Lambertian::new(&texture)))
-                    }
-                    _ => {
-                        return Err(EvalError {
-                            span: Some(texture.span),
-                            message: "Invalid texture type".to_string(),
-                        })
-                    }
-                }
-            }
-            MaterialAST::Metal { color, fuzz } => {
-                let color_val = eval_expr(color, variables, funcs)?;
-                let fuzz = eval_expr(fuzz, variables, funcs)?;
-                match (color_val, fuzz) {
-                    (Value::Vec3(r, g, b), Value::Num(fuzz)) => Value::Material(
-                        MaterialEnum::Metal(Metal::new(&(Color::new(r, g, b) / COLOR_MAX), fuzz)),
-                    ),
-                    _ => {
-                        return Err(EvalError {
-                            span: Some(color.span),
-                            message: "Invalid texture type".to_string(),
-                        })
-                    }
-                }
-            }
-            MaterialAST::Dielectric { reflection_index } => {
-                let reflection_index_val = eval_expr(reflection_index, variables, funcs)?;
-                match reflection_index_val {
-                    Value::Num(reflection_index) => {
-                        Value::Material(MaterialEnum::Dielectric(Dielectric::new(reflection_index)))
-                    }
-                    _ => {
-                        return Err(EvalError {
-                            span: Some(reflection_index.span),
-                            message: "Invalid reflection index type".to_string(),
-                        })
-                    }
-                }
-            }
-            MaterialAST::Light { color, intensity } => {
-                let color_val = eval_expr(color, variables, funcs)?;
-                let intensity = eval_expr(intensity, variables, funcs)?;
-                match (color_val, intensity) {
-                    (Value::Vec3(r, g, b), Value::Num(intensity)) => Value::Material(
-                        MaterialEnum::DiffuseLight(DiffuseLight::new(&TextureEnum::SolidColor(
-                            SolidColor::new(Color::new(r, g, b) / COLOR_MAX * intensity),
-                        ))),
-                    ),
-                    _ => {
-                        return Err(EvalError {
-                            span: Some(color.span),
-                            message: "Invalid color or intensity type".to_string(),
-                        })
-                    }
-                }
-            }
-        },
-        ExprEnum::Texture(tex) => match tex.as_ref() {
-            TextureAST::SolidColor(color) => {
-                let color_val = eval_expr(color, variables, funcs)?;
-                match color_val {
-                    Value::Vec3(r, g, b) => Value::Texture(TextureEnum::SolidColor(
-                        SolidColor::new(Color::new(r, g, b) / COLOR_MAX),
-                    )),
-                    _ => {
-                        return Err(EvalError {
-                            span: Some(color.span),
-                            message: "Invalid color type".to_string(),
-                        })
-                    }
-                }
-            }
-            TextureAST::Checker(odd, even) => {
-                let odd_val = eval_expr(odd, variables, funcs)?;
-                let even = eval_expr(even, variables, funcs)?;
-                match (odd_val, even) {
-                    (Value::Texture(odd), Value::Texture(even)) => {
-                        Value::Texture(TextureEnum::Checker(Checker::new(odd, even)))
-                    }
-                    _ => {
-                        return Err(EvalError {
-                            span: Some(odd.span),
-                            message: "Invalid checker type".to_string(),
-                        })
-                    }
-                }
-            }
-            TextureAST::Perlin(scale) => {
-                let scale_val = eval_expr(scale, variables, funcs)?;
-                match scale_val {
-                    Value::Num(scale) => {
-                        Value::Texture(TextureEnum::NoiseTexture(NoiseTexture::new(scale)))
-                    }
-                    _ => {
-                        return Err(EvalError {
-                            span: Some(scale.span),
-                            message: "Invalid scale type".to_string(),
-                        })
-                    }
-                }
-            }
-        },
+        ExprEnum::Array(members) => {
+            Value::Array(eval_exprs(members.iter().collect(), variables, funcs)?.into())
+        }
+        ExprEnum::Material(mat) => {
+            let args = eval_exprs(material_exprs(mat), variables, funcs)?;
+            build_material(mat, args)?
+        }
+        ExprEnum::Texture(tex) => {
+            let args = eval_exprs(texture_exprs(tex), variables, funcs)?;
+            build_texture(tex, args)?
+        }
     };
     Ok(val)
 }
+
+fn eval_binary_op<'a>(
+    op: BinOp,
+    a: &'a Expression,
+    b: &'a Expression,
+    variables: &mut Variables,
+    funcs: &Functions,
+) -> Result<Value, EvalError<'a>> {
+    let lhs = eval_expr(a, variables, funcs)?;
+    let rhs = eval_expr(b, variables, funcs)?;
+    binary_op(op, lhs, rhs).map_err(|message| EvalError {
+        span: Some(a.span),
+        message,
+    })
+}
@@ -0,0 +1,282 @@
+use super::EvalError;
+use crate::ast::{expression::ExprEnum, Expression, Span};
+use crate::tracer::vec3::Vec3;
+
+/// A function of a point compiled from an `Isosurface` function, so render threads can
+/// evaluate it without the interpreter. Signed distance functions are negative inside.
+pub(super) enum Field {
+    Const(f64),
+    /// A coordinate of the point: 0 for x, 1 for y and 2 for z.
+    Coordinate(usize),
+    Add(Box<Field>, Box<Field>),
+    Sub(Box<Field>, Box<Field>),
+    Mul(Box<Field>, Box<Field>),
+    Div(Box<Field>, Box<Field>),
+    Unary(fn(f64) -> f64, Box<Field>),
+    Binary(fn(f64, f64) -> f64, Box<Field>, Box<Field>),
+    Sphere(Box<Field>),
+    Box(Box<[Field; 3]>),
+    Torus(Box<Field>, Box<Field>),
+    /// Union of two fields blended over a distance.
+    SmoothUnion(Box<Field>, Box<Field>, Box<Field>),
+    /// A field seen from a moved point.
+    Translate(Box<[Field; 3]>, Box<Field>),
+    Scale(Box<Field>, Box<Field>),
+    /// A field rotated about the y axis by an angle growing with y.
+    Twist(Box<Field>, Box<Field>),
+    /// A field repeated every period along each axis; a period of 0 does not repeat.
+    Repeat(Box<[Field; 3]>, Box<Field>),
+}
+
+impl Field {
+    pub(super) fn eval(&self, p: Vec3) -> f64 {
+        match self {
+            Field::Const(n) => *n,
+            Field::Coordinate(axis) => p[*axis],
+            Field::Add(a, b) => a.eval(p) + b.eval(p),
+            Field::Sub(a, b) => a.eval(p) - b.eval(p),
+            Field::Mul(a, b) => a.eval(p) * b.eval(p),
+            Field::Div(a, b) => a.eval(p) / b.eval(p),
+            Field::Unary(f, a) => f(a.eval(p)),
+            Field::Binary(f, a, b) => f(a.eval(p), b.eval(p)),
+            Field::Sphere(radius) => p.length() - radius.eval(p),
+            Field::Box(half) => {
+                let q = [0, 1, 2].map(|axis| p[axis].abs() - half[axis].eval(p));
+                let outside = Vec3::new(q[0].max(0.0), q[1].max(0.0), q[2].max(0.0)).length();
+                outside + q[0].max(q[1]).max(q[2]).min(0.0)
+            }
+            Field::Torus(major, minor) => {
+                let ring = (p.x() * p.x() + p.z() * p.z()).sqrt() - major.eval(p);
+                (ring * ring + p.y() * p.y()).sqrt() - minor.eval(p)
+            }
+            Field::SmoothUnion(a, b, k) => {
+                let (a, b, k) = (a.eval(p), b.eval(p), k.eval(p));
+                if k <= 0.0 {
+                    return a.min(b);
+                }
+                let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+                b + (a - b) * h - k * h * (1.0 - h)
+            }
+            Field::Translate(offset, field) => {
+                let offset = Vec3::new(offset[0].eval(p), offset[1].eval(p), offset[2].eval(p));
+                field.eval(p - offset)
+            }
+            Field::Scale(factor, field) => {
+                let factor = factor.eval(p);
+                field.eval(p / factor) * factor
+            }
+            Field::Twist(rate, field) => {
+                let (sin, cos) = (rate.eval(p) * p.y()).to_radians().sin_cos();
+                field.eval(Vec3::new(
+                    cos * p.x() + sin * p.z(),
+                    p.y(),
+                    -sin * p.x() + cos * p.z(),
+                ))
+            }
+            Field::Repeat(period, field) => {
+                let q = [0, 1, 2].map(|axis| {
+                    let period = period[axis].eval(p);
+                    if period == 0.0 {
+                        p[axis]
+                    } else {
+                        p[axis] - period * (p[axis] / period).round()
+                    }
+                });
+                field.eval(Vec3::new(q[0], q[1], q[2]))
+            }
+        }
+    }
+}
+
+/// The scene variables `expr` reads besides the `coordinates` and `PI`, once each in the order
+/// they first appear, with the expressions reading them. Both evaluators evaluate those like
+/// any other property, and the object gets their values.
+pub(super) fn variables<'a>(
+    expr: &'a Expression<'a>,
+    coordinates: &[&str],
+) -> Vec<(&'a str, &'a Expression<'a>)> {
+    fn walk<'a>(
+        expr: &'a Expression<'a>,
+        coordinates: &[&str],
+        found: &mut Vec<(&'a str, &'a Expression<'a>)>,
+    ) {
+        match &expr.expr {
+            ExprEnum::Ident(ident) => {
+                let name = *ident.fragment();
+                let seen = found.iter().any(|(n, _)| *n == name);
+                if name != "PI" && !coordinates.contains(&name) && !seen {
+                    found.push((name, expr));
+                }
+            }
+            ExprEnum::Add(a, b)
+            | ExprEnum::Sub(a, b)
+            | ExprEnum::Mul(a, b)
+            | ExprEnum::Div(a, b)
+            | ExprEnum::And(a, b)
+            | ExprEnum::Or(a, b)
+            | ExprEnum::Gt(a, b)
+            | ExprEnum::Ge(a, b)
+            | ExprEnum::Lt(a, b)
+            | ExprEnum::Le(a, b)
+            | ExprEnum::Eq(a, b)
+            | ExprEnum::Neq(a, b) => {
+                walk(a, coordinates, found);
+                walk(b, coordinates, found);
+            }
+            ExprEnum::Not(a) => walk(a, coordinates, found),
+            ExprEnum::Vec3(x, y, z) => {
+                walk(x, coordinates, found);
+                walk(y, coordinates, found);
+                walk(z, coordinates, found);
+            }
+            ExprEnum::FnInvoke(_, args) => {
+                args.iter().for_each(|arg| walk(arg, coordinates, found))
+            }
+            _ => {}
+        }
+    }
+    let mut found = Vec::new();
+    walk(expr, coordinates, &mut found);
+    found
+}
+
+/// Compiles `expr`, a number in terms of `x`, `y` and `z`, in which the names of `constants`
+/// stand for their values.
+pub(super) fn compile<'a>(
+    expr: &'a Expression<'a>,
+    constants: &[(&str, f64)],
+) -> Result<Field, EvalError<'a>> {
+    let error = |message: &str| EvalError {
+        span: Some(expr.span),
+        message: message.to_string(),
+    };
+    let binary = |a: &'a Expression<'a>, b: &'a Expression<'a>| -> Result<_, EvalError<'a>> {
+        Ok((
+            Box::new(compile(a, constants)?),
+            Box::new(compile(b, constants)?),
+        ))
+    };
+    Ok(match &expr.expr {
+        ExprEnum::NumLiteral(n) => Field::Const(*n),
+        ExprEnum::Ident(ident) => match *ident.fragment() {
+            "x" => Field::Coordinate(0),
+            "y" => Field::Coordinate(1),
+            "z" => Field::Coordinate(2),
+            "PI" => Field::Const(std::f64::consts::PI),
+            name => match constants.iter().find(|(constant, _)| *constant == name) {
+                Some((_, value)) => Field::Const(*value),
+                None => {
+                    return Err(EvalError {
+                        span: Some(*ident),
+                        message: format!("\"{}\" is not a number", name),
+                    })
+                }
+            },
+        },
+        ExprEnum::Add(a, b) => {
+            let (a, b) = binary(a, b)?;
+            Field::Add(a, b)
+        }
+        ExprEnum::Sub(a, b) => {
+            let (a, b) = binary(a, b)?;
+            Field::Sub(a, b)
+        }
+        ExprEnum::Mul(a, b) => {
+            let (a, b) = binary(a, b)?;
+            Field::Mul(a, b)
+        }
+        ExprEnum::Div(a, b) => {
+            let (a, b) = binary(a, b)?;
+            Field::Div(a, b)
+        }
+        ExprEnum::FnInvoke(name, args) => invoke(*name, args, constants)?,
+        _ => return Err(error("Isosurface function must be a number")),
+    })
+}
+
+/// The field of calling `name`, which is a math function, an SDF or an operation on SDFs.
+fn invoke<'a>(
+    name: Span<'a>,
+    args: &'a [Expression<'a>],
+    constants: &[(&str, f64)],
+) -> Result<Field, EvalError<'a>> {
+    let error = |message: String| EvalError {
+        span: Some(name),
+        message,
+    };
+    let name = *name.fragment();
+    let unary: Option<fn(f64) -> f64> = match name {
+        "sqrt" => Some(f64::sqrt),
+        "sin" => Some(f64::sin),
+        "cos" => Some(f64::cos),
+        "tan" => Some(f64::tan),
+        "asin" => Some(f64::asin),
+        "acos" => Some(f64::acos),
+        "atan" => Some(f64::atan),
+        "exp" => Some(f64::exp),
+        "log10" => Some(f64::log10),
+        "abs" => Some(f64::abs),
+        _ => None,
+    };
+    let binary: Option<fn(f64, f64) -> f64> = match name {
+        "atan2" => Some(f64::atan2),
+        "pow" => Some(f64::powf),
+        "log" => Some(f64::log),
+        "min" | "union" => Some(f64::min),
+        "max" | "intersection" => Some(f64::max),
+        "difference" => Some(|a, b| a.max(-b)),
+        _ => None,
+    };
+    let arity = match (unary, binary, name) {
+        (Some(_), _, _) => 1,
+        (_, Some(_), _) => 2,
+        (_, _, "sphere" | "box") => 1,
+        (_, _, "torus" | "translate" | "scale" | "twist" | "repeat") => 2,
+        (_, _, "smooth_union") => 3,
+        _ => {
+            return Err(error(format!(
+                "Unknown function \"{}\" in Isosurface",
+                name
+            )))
+        }
+    };
+    if args.len() != arity {
+        let plural = if arity == 1 { "" } else { "s" };
+        return Err(error(format!(
+            "\"{}\" takes {} argument{}",
+            name, arity, plural
+        )));
+    }
+    let field = |i: usize| compile(&args[i], constants).map(Box::new);
+    let vector = |i: usize| vector(&args[i], constants);
+    Ok(match (unary, binary, name) {
+        (Some(f), _, _) => Field::Unary(f, field(0)?),
+        (_, Some(f), _) => Field::Binary(f, field(0)?, field(1)?),
+        (_, _, "sphere") => Field::Sphere(field(0)?),
+        (_, _, "box") => Field::Box(vector(0)?),
+        (_, _, "torus") => Field::Torus(field(0)?, field(1)?),
+        (_, _, "smooth_union") => Field::SmoothUnion(field(0)?, field(1)?, field(2)?),
+        (_, _, "translate") => Field::Translate(vector(0)?, field(1)?),
+        (_, _, "scale") => Field::Scale(field(0)?, field(1)?),
+        (_, _, "twist") => Field::Twist(field(0)?, field(1)?),
+        _ => Field::Repeat(vector(0)?, field(1)?),
+    })
+}
+
+/// A `Vec3` argument, e.g. the half size of `box`.
+fn vector<'a>(
+    expr: &'a Expression<'a>,
+    constants: &[(&str, f64)],
+) -> Result<Box<[Field; 3]>, EvalError<'a>> {
+    match &expr.expr {
+        ExprEnum::Vec3(x, y, z) => Ok(Box::new([
+            compile(x, constants)?,
+            compile(y, constants)?,
+            compile(z, constants)?,
+        ])),
+        _ => Err(EvalError {
+            span: Some(expr.span),
+            message: "Expected a Vec3 like <1, 2, 3>".to_string(),
+        }),
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use super::value::Value;
+use super::{spline::spline, value::Value};
 
 use rand::Rng;
 
@@ -77,6 +77,12 @@ pub fn standard_functions<'src>() -> Functions<'src> {
             }),
         }),
     );
+    funcs.insert(
+        "spline".to_string(),
+        FnDecl::Native(NativeFn {
+            code: Box::new(spline),
+        }),
+    );
     funcs
 }
 
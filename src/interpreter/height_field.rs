@@ -0,0 +1,140 @@
+use super::{
+    expression::eval_expr, funcs::standard_functions, limits::Budget, mesh::Mesh, value::Value,
+    EvalError,
+};
+use crate::ast::Expression;
+use crate::tracer::vec3::{unit_vector, Vec3};
+use std::collections::HashMap;
+
+/// Heights from 0 to 1 at `columns` × `rows` points, row by row. Columns go along x and rows
+/// along z.
+pub(super) struct Heights {
+    columns: usize,
+    rows: usize,
+    values: Vec<f64>,
+}
+
+impl Heights {
+    /// The brightness of the image at `path`; the first row of pixels is at z = 0.
+    pub(super) fn from_image(path: &str) -> Result<Self, String> {
+        let image = image::open(path)
+            .map_err(|e| format!("Cannot read \"{}\": {}", path, e))?
+            .to_luma16();
+        let (width, height) = image.dimensions();
+        if width < 2 || height < 2 {
+            return Err(format!("\"{}\" needs at least 2 × 2 pixels", path));
+        }
+        Ok(Heights {
+            columns: width as usize,
+            rows: height as usize,
+            values: image
+                .pixels()
+                .map(|pixel| pixel.0[0] as f64 / u16::MAX as f64)
+                .collect(),
+        })
+    }
+
+    /// `function` of `x` and `z` at `resolution` × `resolution` points from 0 to 1, in which the
+    /// names of `constants` stand for their values. Each point counts as a statement against
+    /// the evaluation limits.
+    pub(super) fn from_function<'a>(
+        function: &'a Expression<'a>,
+        resolution: usize,
+        constants: &[(&str, f64)],
+        budget: &mut Budget<'a>,
+    ) -> Result<Self, EvalError<'a>> {
+        let funcs = standard_functions();
+        let mut variables: HashMap<_, _> = constants
+            .iter()
+            .map(|(name, value)| (name.to_string(), Value::Num(*value)))
+            .collect();
+        let mut values = Vec::with_capacity(resolution * resolution);
+        for row in 0..resolution {
+            for column in 0..resolution {
+                budget.statement().map_err(|message| EvalError {
+                    span: Some(function.span),
+                    message,
+                })?;
+                let x = column as f64 / (resolution - 1) as f64;
+                let z = row as f64 / (resolution - 1) as f64;
+                variables.insert("x".to_string(), Value::Num(x));
+                variables.insert("z".to_string(), Value::Num(z));
+                match eval_expr(function, &mut variables, &funcs)? {
+                    Value::Num(height) if height.is_finite() => values.push(height),
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(function.span),
+                            message: format!(
+                                "HeightField function must give a finite number; at x = {}, z = {} it does not",
+                                x, z
+                            ),
+                        })
+                    }
+                }
+            }
+        }
+        Ok(Heights {
+            columns: resolution,
+            rows: resolution,
+            values,
+        })
+    }
+
+    fn get(&self, column: usize, row: usize) -> f64 {
+        self.values[row * self.columns + column]
+    }
+
+    /// Two triangles per grid cell over the box from the origin to `size`, with normals from
+    /// the slope at each point so the terrain shades smoothly. Texture coordinates go from 0 to
+    /// 1 along x and z.
+    pub(super) fn mesh(&self, size: Vec3) -> Mesh {
+        let dx = size.x() / (self.columns - 1) as f64;
+        let dz = size.z() / (self.rows - 1) as f64;
+        let index = |column: usize, row: usize| row * self.columns + column;
+
+        let mut vertices = Vec::with_capacity(self.values.len());
+        let mut normals = Vec::with_capacity(self.values.len());
+        let mut uvs = Vec::with_capacity(self.values.len());
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                vertices.push(Vec3::new(
+                    column as f64 * dx,
+                    self.get(column, row) * size.y(),
+                    row as f64 * dz,
+                ));
+                // central differences, one-sided at the edges
+                let (left, right) = (column.saturating_sub(1), (column + 1).min(self.columns - 1));
+                let (back, front) = (row.saturating_sub(1), (row + 1).min(self.rows - 1));
+                let slope_x = (self.get(right, row) - self.get(left, row)) * size.y()
+                    / ((right - left) as f64 * dx);
+                let slope_z = (self.get(column, front) - self.get(column, back)) * size.y()
+                    / ((front - back) as f64 * dz);
+                normals.push(unit_vector(&Vec3::new(-slope_x, 1.0, -slope_z)));
+                uvs.push((
+                    column as f64 / (self.columns - 1) as f64,
+                    row as f64 / (self.rows - 1) as f64,
+                ));
+            }
+        }
+
+        let mut faces = Vec::with_capacity(2 * (self.columns - 1) * (self.rows - 1));
+        for row in 0..self.rows - 1 {
+            for column in 0..self.columns - 1 {
+                let a = index(column, row);
+                let b = index(column + 1, row);
+                let c = index(column, row + 1);
+                let d = index(column + 1, row + 1);
+                // in this order the faces point up, to +y
+                faces.push([a, c, b]);
+                faces.push([b, c, d]);
+            }
+        }
+        Mesh {
+            vertices,
+            faces,
+            normals: Some(normals),
+            uvs: Some(uvs),
+            colors: None,
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::ast::Span;
+use std::time::{Duration, Instant};
+
+/// Upper bounds on scene evaluation. `None` means unlimited.
+#[derive(Debug, Clone, Default)]
+pub struct EvalLimits {
+    /// Iterations of a single `while` loop, counted each time the loop is entered.
+    pub max_loop_iterations: Option<u64>,
+    /// Statements executed in total, counting each point a `HeightField` function is sampled at.
+    pub max_statements: Option<u64>,
+    /// Objects built in total, including the children of groups.
+    pub max_objects: Option<usize>,
+    /// Wall time spent evaluating.
+    /// `std::time::Instant` is unavailable on `wasm32-unknown-unknown`, so leave this unset there.
+    pub max_time: Option<Duration>,
+}
+
+/// How often the wall time is checked, in statements or loop iterations.
+const TIME_CHECK_INTERVAL: u64 = 1024;
+
+/// Evaluation counters checked against `EvalLimits`.
+pub(super) struct Budget<'a> {
+    limits: EvalLimits,
+    statements: u64,
+    objects: usize,
+    ticks: u64,
+    start: Option<Instant>,
+    /// Spans of the `while` statements being executed by the tree-walker, innermost last.
+    loop_spans: Vec<Span<'a>>,
+}
+
+impl<'a> Budget<'a> {
+    pub(super) fn new(limits: &EvalLimits) -> Self {
+        Self {
+            limits: limits.clone(),
+            statements: 0,
+            objects: 0,
+            ticks: 0,
+            start: limits.max_time.map(|_| Instant::now()),
+            loop_spans: Vec::new(),
+        }
+    }
+
+    pub(super) fn statement(&mut self) -> Result<(), String> {
+        self.statements += 1;
+        if let Some(max) = self.limits.max_statements {
+            if self.statements > max {
+                return Err(format!("Statement limit ({}) exceeded", max));
+            }
+        }
+        self.check_time()
+    }
+
+    /// Record that a loop is about to run its `iterations`-th iteration.
+    pub(super) fn loop_iteration(&mut self, iterations: u64) -> Result<(), String> {
+        if let Some(max) = self.limits.max_loop_iterations {
+            if iterations > max {
+                return Err(format!("Loop iteration limit ({}) exceeded", max));
+            }
+        }
+        self.check_time()
+    }
+
+    pub(super) fn object(&mut self) -> Result<(), String> {
+        self.objects += 1;
+        if let Some(max) = self.limits.max_objects {
+            if self.objects > max {
+                return Err(format!("Object limit ({}) exceeded", max));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_time(&mut self) -> Result<(), String> {
+        self.ticks += 1;
+        if self.ticks < TIME_CHECK_INTERVAL {
+            return Ok(());
+        }
+        self.ticks = 0;
+        if let (Some(start), Some(max)) = (self.start, self.limits.max_time) {
+            if start.elapsed() > max {
+                return Err(format!("Evaluation time limit ({:?}) exceeded", max));
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn enter_loop(&mut self, span: Span<'a>) {
+        self.loop_spans.push(span);
+    }
+
+    pub(super) fn exit_loop(&mut self) {
+        self.loop_spans.pop();
+    }
+
+    /// Span to report a limit error at: the innermost running loop, or `fallback` outside loops.
+    pub(super) fn error_span(&self, fallback: Option<Span<'a>>) -> Option<Span<'a>> {
+        self.loop_spans.last().copied().or(fallback)
+    }
+}
@@ -0,0 +1,135 @@
+use super::{
+    object::{MOTION_END, MOTION_START},
+    value::Value,
+};
+use crate::tracer::{
+    hittable::{BvhNode, HittableEnum, Triangle},
+    material::{Lambertian, MaterialEnum},
+    texture::{TextureEnum, VertexColors},
+    vec3::{cross, Color, Point3, Vec3},
+};
+
+/// Indexed triangles. `normals`, `uvs` and `colors`, if any, have one entry per vertex.
+pub(super) struct Mesh {
+    pub(super) vertices: Vec<Point3>,
+    pub(super) faces: Vec<[usize; 3]>,
+    pub(super) normals: Option<Vec<Vec3>>,
+    pub(super) uvs: Option<Vec<(f64, f64)>>,
+    pub(super) colors: Option<Vec<Color>>,
+}
+
+impl Mesh {
+    /// The triangles of the mesh under their own BVH. Degenerate triangles are skipped since
+    /// models often contain some. Without `material`, the triangles are diffuse with their
+    /// vertex colors.
+    pub(super) fn build(self, material: Option<MaterialEnum>) -> Result<HittableEnum, String> {
+        let mut triangles: Vec<HittableEnum> = Vec::with_capacity(self.faces.len());
+        for face in self.faces.iter() {
+            let [a, b, c] = face.map(|i| self.vertices[i]);
+            if cross(&(b - a), &(c - a)).length() == 0.0 {
+                continue;
+            }
+            let (triangle_material, uvs) = match (&material, &self.colors) {
+                (Some(material), _) => (
+                    material.clone(),
+                    self.uvs.as_ref().map(|uvs| face.map(|i| uvs[i])),
+                ),
+                // the texture coordinates of the corners make the texture coordinates of a hit
+                // its barycentric weights of the second and third corner
+                (None, Some(colors)) => (
+                    MaterialEnum::Lambertian(Lambertian::new(&TextureEnum::VertexColors(
+                        VertexColors::new(face.map(|i| colors[i])),
+                    ))),
+                    Some([(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]),
+                ),
+                (None, None) => return Err("Mesh needs a material or vertex colors".to_string()),
+            };
+            let mut triangle = Triangle::new(&a, &b, &c, triangle_material);
+            if let Some(normals) = self.normals.as_ref() {
+                triangle = triangle.with_normals(face.map(|i| normals[i]));
+            }
+            if let Some(uvs) = uvs {
+                triangle = triangle.with_uvs(uvs);
+            }
+            triangles.push(HittableEnum::Triangle(triangle));
+        }
+        if triangles.is_empty() {
+            return Err("Mesh has no faces with an area".to_string());
+        }
+        Ok(HittableEnum::BvhNode(Box::new(BvhNode::new(
+            &mut triangles,
+            MOTION_START,
+            MOTION_END,
+        ))))
+    }
+}
+
+/// An array of `Vec3`, e.g. `vertices` or `normals`.
+pub(super) fn vec3_array(value: Value) -> Option<Vec<Vec3>> {
+    let Value::Array(members) = value else {
+        return None;
+    };
+    members
+        .iter()
+        .map(|member| match *member {
+            Value::Vec3(x, y, z) => Some(Vec3::new(x, y, z)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// An array of `[u, v]` texture coordinates.
+pub(super) fn uv_array(value: Value) -> Option<Vec<(f64, f64)>> {
+    let Value::Array(members) = value else {
+        return None;
+    };
+    members
+        .iter()
+        .map(|member| match member {
+            Value::Array(uv) => match uv[..] {
+                [Value::Num(u), Value::Num(v)] => Some((u, v)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Faces as arrays of at least 3 vertex indices, split into triangles fanning out from the
+/// first vertex of each face.
+pub(super) fn faces(value: Value, vertex_count: usize) -> Result<Vec<[usize; 3]>, String> {
+    let invalid = || "faces must be an array of arrays of vertex indices".to_string();
+    let Value::Array(faces) = value else {
+        return Err(invalid());
+    };
+    let mut triangles = Vec::with_capacity(faces.len());
+    for face in faces.iter() {
+        let Value::Array(indices) = face else {
+            return Err(invalid());
+        };
+        let indices = indices
+            .iter()
+            .map(|index| match *index {
+                Value::Num(n) if n >= 0.0 && n.fract() == 0.0 => {
+                    let n = n as usize;
+                    if n < vertex_count {
+                        Ok(n)
+                    } else {
+                        Err(format!(
+                            "Vertex index {} is out of range for {} vertices",
+                            n, vertex_count
+                        ))
+                    }
+                }
+                _ => Err(invalid()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if indices.len() < 3 {
+            return Err("A face needs at least 3 vertices".to_string());
+        }
+        for i in 1..indices.len() - 1 {
+            triangles.push([indices[0], indices[i], indices[i + 1]]);
+        }
+    }
+    Ok(triangles)
+}
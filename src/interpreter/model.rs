@@ -0,0 +1,109 @@
+mod obj;
+mod ply;
+mod stl;
+
+use super::{
+    mesh::Mesh,
+    object::{MOTION_END, MOTION_START},
+};
+use crate::tracer::{
+    hittable::{BvhNode, HittableEnum},
+    material::MaterialEnum,
+    vec3::Vec3,
+};
+use std::{fs, path::Path};
+
+/// Meshes of a model file with their material, `None` for meshes shaded with vertex colors.
+type Parts = Vec<(Mesh, Option<MaterialEnum>)>;
+
+/// Loads the OBJ, STL or PLY file at `path`, picking the format from the extension. With
+/// `normalize`, the model is moved and scaled to fit the box from <-0.5, -0.5, -0.5> to
+/// <0.5, 0.5, 0.5>.
+pub(super) fn load_model(
+    path: &str,
+    material: Option<MaterialEnum>,
+    normalize: bool,
+) -> Result<HittableEnum, String> {
+    let file = Path::new(path);
+    let extension = file
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    let load = match extension.as_deref() {
+        Some("obj") => obj::load,
+        Some("stl") => stl::load,
+        Some("ply") => ply::load,
+        _ => return Err(format!("\"{}\" is not an OBJ, STL or PLY file", path)),
+    };
+    let data = fs::read(file).map_err(|e| format!("Cannot read \"{}\": {}", path, e))?;
+    let mut parts =
+        load(file, &data, material.as_ref()).map_err(|e| format!("\"{}\": {}", path, e))?;
+    if parts.iter().all(|(mesh, _)| mesh.faces.is_empty()) {
+        return Err(format!("\"{}\" has no faces", path));
+    }
+    if normalize {
+        normalize_parts(&mut parts);
+    }
+
+    let mut meshes = parts
+        .into_iter()
+        .filter(|(mesh, _)| !mesh.faces.is_empty())
+        .map(|(mesh, material)| mesh.build(material))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("\"{}\": {}", path, e))?;
+    Ok(match meshes.len() {
+        1 => meshes.pop().unwrap(),
+        _ => HittableEnum::BvhNode(Box::new(BvhNode::new(
+            &mut meshes,
+            MOTION_START,
+            MOTION_END,
+        ))),
+    })
+}
+
+/// Centers the vertices used by faces at the origin and scales them uniformly so that the
+/// longest side of their bounding box is 1.
+fn normalize_parts(parts: &mut Parts) {
+    let mut min = [f64::INFINITY; 3];
+    let mut max = [f64::NEG_INFINITY; 3];
+    for (mesh, _) in parts.iter() {
+        for face in mesh.faces.iter() {
+            for &i in face.iter() {
+                let v = mesh.vertices[i];
+                for (axis, value) in [v.x(), v.y(), v.z()].into_iter().enumerate() {
+                    min[axis] = min[axis].min(value);
+                    max[axis] = max[axis].max(value);
+                }
+            }
+        }
+    }
+    let center = Vec3::new(
+        (min[0] + max[0]) / 2.0,
+        (min[1] + max[1]) / 2.0,
+        (min[2] + max[2]) / 2.0,
+    );
+    let size = (0..3).map(|axis| max[axis] - min[axis]).fold(0.0, f64::max);
+    // a model flat in every direction, i.e. a point, cannot be scaled up
+    let scale = if size > 0.0 { 1.0 / size } else { 1.0 };
+    for (mesh, _) in parts.iter_mut() {
+        for v in mesh.vertices.iter_mut() {
+            *v = (*v - center) * scale;
+        }
+    }
+}
+
+fn parse_numbers(args: &[&str]) -> Result<Vec<f64>, String> {
+    args.iter()
+        .map(|arg| {
+            arg.parse::<f64>()
+                .map_err(|_| format!("Invalid number \"{}\"", arg))
+        })
+        .collect()
+}
+
+fn parse_vec3(args: &[&str]) -> Result<Vec3, String> {
+    match parse_numbers(args)?[..] {
+        [x, y, z, ..] => Ok(Vec3::new(x, y, z)),
+        _ => Err("Expected 3 numbers".to_string()),
+    }
+}
@@ -0,0 +1,314 @@
+use super::{parse_numbers, parse_vec3, Mesh, Parts};
+use crate::tracer::{
+    material::{Dielectric, DiffuseLight, Lambertian, MaterialEnum, Metal},
+    texture::{ImageTexture, SolidColor, TextureEnum},
+    vec3::{Color, Point3, Vec3},
+};
+use std::{collections::HashMap, fs, path::Path};
+
+/// Triangles of an OBJ file sharing a material, with their vertices de-indexed so that each
+/// corner has a single index for its position, normal and texture coordinates.
+#[derive(Default)]
+struct Group {
+    corners: HashMap<(usize, Option<usize>, Option<usize>), usize>,
+    vertices: Vec<Point3>,
+    normals: Vec<Option<Vec3>>,
+    uvs: Vec<Option<(f64, f64)>>,
+    faces: Vec<[usize; 3]>,
+}
+
+impl Group {
+    fn corner(&mut self, obj: &Obj, corner: (usize, Option<usize>, Option<usize>)) -> usize {
+        if let Some(index) = self.corners.get(&corner) {
+            return *index;
+        }
+        let (v, vt, vn) = corner;
+        self.vertices.push(obj.positions[v]);
+        self.uvs.push(vt.map(|vt| obj.uvs[vt]));
+        self.normals.push(vn.map(|vn| obj.normals[vn]));
+        self.corners.insert(corner, self.vertices.len() - 1);
+        self.vertices.len() - 1
+    }
+
+    /// Normals and texture coordinates are only used if every corner has them.
+    fn into_mesh(self) -> Mesh {
+        Mesh {
+            vertices: self.vertices,
+            faces: self.faces,
+            normals: self.normals.into_iter().collect(),
+            uvs: self.uvs.into_iter().collect(),
+            colors: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Obj {
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<(f64, f64)>,
+    mtllibs: Vec<String>,
+}
+
+/// Faces grouped by the name of their MTL material, in the order the groups appear.
+type Groups = Vec<(Option<String>, Group)>;
+
+/// Meshes of a Wavefront OBJ file. Faces take `material` if given, or else the MTL material
+/// they select with `usemtl`.
+pub(super) fn load(
+    path: &Path,
+    data: &[u8],
+    material: Option<&MaterialEnum>,
+) -> Result<Parts, String> {
+    let source = String::from_utf8_lossy(data);
+    let (obj, groups) = parse_obj(&source, material.is_some())
+        .map_err(|(line, message)| format!("line {}: {}", line, message))?;
+
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut materials = HashMap::new();
+    if material.is_none() {
+        for mtllib in obj.mtllibs.iter() {
+            let mtl_path = dir.join(mtllib);
+            let source = fs::read_to_string(&mtl_path)
+                .map_err(|e| format!("Cannot read \"{}\": {}", mtl_path.display(), e))?;
+            materials.extend(
+                parse_mtl(&source, mtl_path.parent().unwrap_or(dir)).map_err(
+                    |(line, message)| format!("{}:{}: {}", mtl_path.display(), line, message),
+                )?,
+            );
+        }
+    }
+
+    let mut parts = Vec::new();
+    for (name, group) in groups {
+        if group.faces.is_empty() {
+            continue;
+        }
+        let group_material = match (material, name) {
+            (Some(material), _) => material.clone(),
+            (None, Some(name)) => materials
+                .get(&name)
+                .cloned()
+                .ok_or_else(|| format!("Material \"{}\" not found in the MTL files", name))?,
+            (None, None) => {
+                return Err("Faces without a material; give the Model a material".to_string())
+            }
+        };
+        parts.push((group.into_mesh(), Some(group_material)));
+    }
+    Ok(parts)
+}
+
+/// Errors are the line number and a message.
+fn parse_obj(source: &str, single_group: bool) -> Result<(Obj, Groups), (usize, String)> {
+    let mut obj = Obj::default();
+    let mut groups: Groups = vec![(None, Group::default())];
+    let mut current = 0;
+    for (number, line) in source.lines().enumerate() {
+        let error = |message: String| (number + 1, message);
+        let line = line.split('#').next().unwrap_or("");
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+        match keyword {
+            "v" => obj.positions.push(parse_vec3(&args).map_err(error)?),
+            "vn" => obj.normals.push(parse_vec3(&args).map_err(error)?),
+            "vt" => {
+                let uv = parse_numbers(&args).map_err(error)?;
+                match uv[..] {
+                    [u] => obj.uvs.push((u, 0.0)),
+                    [u, v, ..] => obj.uvs.push((u, v)),
+                    _ => return Err(error("vt needs texture coordinates".to_string())),
+                }
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(error("A face needs at least 3 vertices".to_string()));
+                }
+                let corners = args
+                    .iter()
+                    .map(|arg| parse_corner(arg, &obj))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(error)?;
+                let group = &mut groups[current].1;
+                let indices: Vec<usize> =
+                    corners.into_iter().map(|c| group.corner(&obj, c)).collect();
+                // fan out from the first corner, which splits convex polygons into triangles
+                for i in 1..indices.len() - 1 {
+                    group.faces.push([indices[0], indices[i], indices[i + 1]]);
+                }
+            }
+            "usemtl" if !single_group => {
+                let name = args.join(" ");
+                current = match groups.iter().position(|(n, _)| n.as_deref() == Some(&name)) {
+                    Some(index) => index,
+                    None => {
+                        groups.push((Some(name), Group::default()));
+                        groups.len() - 1
+                    }
+                };
+            }
+            "mtllib" => obj.mtllibs.extend(args.iter().map(|s| s.to_string())),
+            // objects, groups, smoothing groups, lines and points do not change the geometry
+            _ => {}
+        }
+    }
+    Ok((obj, groups))
+}
+
+/// A face corner `v`, `v/vt`, `v//vn` or `v/vt/vn` as 0-based indices.
+fn parse_corner(arg: &str, obj: &Obj) -> Result<(usize, Option<usize>, Option<usize>), String> {
+    let mut parts = arg.split('/');
+    let v = parse_index(parts.next(), obj.positions.len(), arg)?
+        .ok_or_else(|| format!("Invalid face vertex \"{}\"", arg))?;
+    let vt = parse_index(parts.next(), obj.uvs.len(), arg)?;
+    let vn = parse_index(parts.next(), obj.normals.len(), arg)?;
+    Ok((v, vt, vn))
+}
+
+/// OBJ indices start from 1, and negative ones count back from the last element so far.
+fn parse_index(part: Option<&str>, count: usize, arg: &str) -> Result<Option<usize>, String> {
+    let part = match part {
+        Some(part) if !part.is_empty() => part,
+        _ => return Ok(None),
+    };
+    let index: i64 = part
+        .parse()
+        .map_err(|_| format!("Invalid face vertex \"{}\"", arg))?;
+    let index = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+    if index < 0 || index >= count as i64 {
+        return Err(format!(
+            "Face vertex \"{}\" refers to a missing element",
+            arg
+        ));
+    }
+    Ok(Some(index as usize))
+}
+
+/// Properties of an MTL material used to pick the closest KOV-Ray material.
+struct Mtl {
+    kd: Color,
+    ks: Color,
+    ke: Color,
+    ns: f64,
+    ni: f64,
+    dissolve: f64,
+    illum: u32,
+    map_kd: Option<TextureEnum>,
+}
+
+impl Default for Mtl {
+    fn default() -> Self {
+        Self {
+            kd: Color::new(0.8, 0.8, 0.8),
+            ks: Color::zero(),
+            ke: Color::zero(),
+            ns: 0.0,
+            ni: 1.5,
+            dissolve: 1.0,
+            illum: 2,
+            map_kd: None,
+        }
+    }
+}
+
+impl Mtl {
+    /// Emissive materials become lights, transparent ones dielectrics, and reflective ones
+    /// metals whose fuzz decreases as the specular exponent `Ns` grows to its maximum 1000.
+    fn material(self) -> MaterialEnum {
+        let is_black = |c: Color| c.x() == 0.0 && c.y() == 0.0 && c.z() == 0.0;
+        if !is_black(self.ke) {
+            MaterialEnum::DiffuseLight(DiffuseLight::new(&TextureEnum::SolidColor(
+                SolidColor::new(self.ke),
+            )))
+        } else if self.dissolve < 1.0 || matches!(self.illum, 4 | 6 | 7 | 9) {
+            MaterialEnum::Dielectric(Dielectric::new(self.ni))
+        } else if !is_black(self.ks) && (self.illum == 3 || is_black(self.kd)) {
+            let fuzz = (1.0 - self.ns / 1000.0).clamp(0.0, 1.0);
+            MaterialEnum::Metal(Metal::new(&self.ks, fuzz))
+        } else {
+            let texture = self
+                .map_kd
+                .unwrap_or(TextureEnum::SolidColor(SolidColor::new(self.kd)));
+            MaterialEnum::Lambertian(Lambertian::new(&texture))
+        }
+    }
+}
+
+/// Materials by name. Texture files are relative to `dir`.
+fn parse_mtl(source: &str, dir: &Path) -> Result<HashMap<String, MaterialEnum>, (usize, String)> {
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, Mtl)> = None;
+    for (number, line) in source.lines().enumerate() {
+        let error = |message: String| (number + 1, message);
+        let line = line.split('#').next().unwrap_or("");
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+        if keyword == "newmtl" {
+            if let Some((name, mtl)) = current.take() {
+                materials.insert(name, mtl.material());
+            }
+            current = Some((args.join(" "), Mtl::default()));
+            continue;
+        }
+        let Some((_, mtl)) = current.as_mut() else {
+            continue;
+        };
+        match keyword {
+            "Kd" => mtl.kd = parse_vec3(&args).map_err(error)?,
+            "Ks" => mtl.ks = parse_vec3(&args).map_err(error)?,
+            "Ke" => mtl.ke = parse_vec3(&args).map_err(error)?,
+            "Ns" | "Ni" | "d" | "Tr" => {
+                let value = match parse_numbers(&args).map_err(error)?[..] {
+                    [value] => value,
+                    _ => return Err(error(format!("{} needs a number", keyword))),
+                };
+                match keyword {
+                    "Ns" => mtl.ns = value,
+                    "Ni" => mtl.ni = value,
+                    "d" => mtl.dissolve = value,
+                    _ => mtl.dissolve = 1.0 - value,
+                }
+            }
+            "illum" => {
+                mtl.illum = args
+                    .first()
+                    .and_then(|arg| arg.parse().ok())
+                    .ok_or_else(|| error("illum needs an illumination model".to_string()))?
+            }
+            "map_Kd" => {
+                // options come before the file name, which is the last argument
+                let file = args
+                    .last()
+                    .ok_or_else(|| error("map_Kd needs a file".to_string()))?;
+                mtl.map_kd = Some(load_texture(&dir.join(file)).map_err(error)?);
+            }
+            _ => {}
+        }
+    }
+    if let Some((name, mtl)) = current {
+        materials.insert(name, mtl.material());
+    }
+    Ok(materials)
+}
+
+fn load_texture(path: &Path) -> Result<TextureEnum, String> {
+    let image = image::open(path)
+        .map_err(|e| format!("Cannot read \"{}\": {}", path.display(), e))?
+        .to_rgb8();
+    let (width, height) = image.dimensions();
+    Ok(TextureEnum::ImageTexture(ImageTexture::new(
+        width,
+        height,
+        image.into_raw(),
+    )))
+}
@@ -0,0 +1,309 @@
+use super::{Mesh, Parts};
+use crate::tracer::{
+    material::MaterialEnum,
+    vec3::{Color, Vec3},
+};
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Scalar {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => Scalar::Int8,
+            "uchar" | "uint8" => Scalar::UInt8,
+            "short" | "int16" => Scalar::Int16,
+            "ushort" | "uint16" => Scalar::UInt16,
+            "int" | "int32" => Scalar::Int32,
+            "uint" | "uint32" => Scalar::UInt32,
+            "float" | "float32" => Scalar::Float32,
+            "double" | "float64" => Scalar::Float64,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::Int8 | Scalar::UInt8 => 1,
+            Scalar::Int16 | Scalar::UInt16 => 2,
+            Scalar::Int32 | Scalar::UInt32 | Scalar::Float32 => 4,
+            Scalar::Float64 => 8,
+        }
+    }
+}
+
+enum PropertyType {
+    Scalar(Scalar),
+    /// Count type and item type.
+    List(Scalar, Scalar),
+}
+
+struct Property {
+    name: String,
+    kind: PropertyType,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Values of one element, in the order of its properties. Scalars are lists of one value.
+type Row = Vec<Vec<f64>>;
+
+/// Reads the values of the body, in the ASCII or a binary format.
+struct Reader<'a> {
+    format: Format,
+    data: &'a [u8],
+    position: usize,
+    /// Tokens of the ASCII body.
+    tokens: std::str::SplitAsciiWhitespace<'a>,
+}
+
+impl<'a> Reader<'a> {
+    fn scalar(&mut self, scalar: Scalar) -> Result<f64, String> {
+        if self.format == Format::Ascii {
+            let token = self
+                .tokens
+                .next()
+                .ok_or_else(|| "Unexpected end of file".to_string())?;
+            return token
+                .parse()
+                .map_err(|_| format!("Invalid number \"{}\"", token));
+        }
+        let size = scalar.size();
+        let bytes = self
+            .data
+            .get(self.position..self.position + size)
+            .ok_or_else(|| "Unexpected end of file".to_string())?;
+        self.position += size;
+        let mut buffer = [0; 8];
+        buffer[..size].copy_from_slice(bytes);
+        if self.format == Format::BinaryBigEndian {
+            buffer[..size].reverse();
+        }
+        let [b0, b1, b2, b3, ..] = buffer;
+        Ok(match scalar {
+            Scalar::Int8 => b0 as i8 as f64,
+            Scalar::UInt8 => b0 as f64,
+            Scalar::Int16 => i16::from_le_bytes([b0, b1]) as f64,
+            Scalar::UInt16 => u16::from_le_bytes([b0, b1]) as f64,
+            Scalar::Int32 => i32::from_le_bytes([b0, b1, b2, b3]) as f64,
+            Scalar::UInt32 => u32::from_le_bytes([b0, b1, b2, b3]) as f64,
+            Scalar::Float32 => f32::from_le_bytes([b0, b1, b2, b3]) as f64,
+            Scalar::Float64 => f64::from_le_bytes(buffer),
+        })
+    }
+
+    fn row(&mut self, element: &Element) -> Result<Row, String> {
+        element
+            .properties
+            .iter()
+            .map(|property| match property.kind {
+                PropertyType::Scalar(scalar) => Ok(vec![self.scalar(scalar)?]),
+                PropertyType::List(count, item) => {
+                    let count = self.scalar(count)? as usize;
+                    (0..count).map(|_| self.scalar(item)).collect()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Meshes of an ASCII or binary PLY file. Vertex colors (`red`, `green`, `blue`) are used
+/// when no `material` is given; normals (`nx`, `ny`, `nz`) and texture coordinates (`s`, `t`
+/// or `u`, `v`) when every vertex has them.
+pub(super) fn load(
+    _path: &Path,
+    data: &[u8],
+    material: Option<&MaterialEnum>,
+) -> Result<Parts, String> {
+    let (format, elements, body) = parse_header(data)?;
+    let mut reader = Reader {
+        format,
+        data: &data[body..],
+        position: 0,
+        tokens: std::str::from_utf8(&data[body..])
+            .unwrap_or("")
+            .split_ascii_whitespace(),
+    };
+
+    let mut mesh = Mesh {
+        vertices: Vec::new(),
+        faces: Vec::new(),
+        normals: None,
+        uvs: None,
+        colors: None,
+    };
+    for element in elements.iter() {
+        let column = |names: &[&str]| {
+            element
+                .properties
+                .iter()
+                .position(|property| names.contains(&property.name.as_str()))
+        };
+        match element.name.as_str() {
+            "vertex" => {
+                let (Some(x), Some(y), Some(z)) = (column(&["x"]), column(&["y"]), column(&["z"]))
+                else {
+                    return Err("vertex needs x, y and z".to_string());
+                };
+                let normal = (column(&["nx"]), column(&["ny"]), column(&["nz"]));
+                let uv = (
+                    column(&["s", "u", "texture_u", "texture_s"]),
+                    column(&["t", "v", "texture_v", "texture_t"]),
+                );
+                let color = (column(&["red"]), column(&["green"]), column(&["blue"]));
+                // integer colors go up to the maximum of their type, floating point ones to 1
+                let color_max = match color.0.map(|i| &element.properties[i].kind) {
+                    Some(PropertyType::Scalar(Scalar::UInt8)) => 255.0,
+                    Some(PropertyType::Scalar(Scalar::UInt16)) => 65535.0,
+                    _ => 1.0,
+                };
+                let mut normals = Vec::new();
+                let mut uvs = Vec::new();
+                let mut colors = Vec::new();
+                for _ in 0..element.count {
+                    let row = reader.row(element)?;
+                    mesh.vertices
+                        .push(Vec3::new(row[x][0], row[y][0], row[z][0]));
+                    if let (Some(nx), Some(ny), Some(nz)) = normal {
+                        normals.push(Vec3::new(row[nx][0], row[ny][0], row[nz][0]));
+                    }
+                    if let (Some(u), Some(v)) = uv {
+                        uvs.push((row[u][0], row[v][0]));
+                    }
+                    if let (Some(r), Some(g), Some(b)) = color {
+                        colors.push(Color::new(row[r][0], row[g][0], row[b][0]) / color_max);
+                    }
+                }
+                mesh.normals = (!normals.is_empty()).then_some(normals);
+                mesh.uvs = (!uvs.is_empty()).then_some(uvs);
+                mesh.colors = (!colors.is_empty() && material.is_none()).then_some(colors);
+            }
+            "face" => {
+                let Some(indices) = column(&["vertex_indices", "vertex_index"]) else {
+                    return Err("face needs vertex_indices".to_string());
+                };
+                for _ in 0..element.count {
+                    let row = reader.row(element)?;
+                    if row[indices].len() < 3 {
+                        return Err("A face needs at least 3 vertices".to_string());
+                    }
+                    let vertex_count = mesh.vertices.len() as f64;
+                    if let Some(i) = row[indices]
+                        .iter()
+                        .find(|i| **i < 0.0 || **i >= vertex_count || i.fract() != 0.0)
+                    {
+                        return Err(format!(
+                            "Vertex index {} is out of range for {} vertices",
+                            i,
+                            mesh.vertices.len()
+                        ));
+                    }
+                    let face: Vec<usize> = row[indices].iter().map(|i| *i as usize).collect();
+                    // fan out from the first vertex, which splits convex polygons into triangles
+                    for i in 1..face.len() - 1 {
+                        mesh.faces.push([face[0], face[i], face[i + 1]]);
+                    }
+                }
+            }
+            _ => {
+                for _ in 0..element.count {
+                    reader.row(element)?;
+                }
+            }
+        }
+    }
+    if material.is_none() && mesh.colors.is_none() {
+        return Err("PLY file has no vertex colors; give the Model a material".to_string());
+    }
+    Ok(vec![(mesh, material.cloned())])
+}
+
+/// The format, the elements and the offset of the body.
+fn parse_header(data: &[u8]) -> Result<(Format, Vec<Element>, usize), String> {
+    const END: &[u8] = b"end_header";
+    let end = data
+        .windows(END.len())
+        .position(|window| window == END)
+        .ok_or_else(|| "Not a PLY file".to_string())?;
+    // the body starts after the line break ending the header
+    let body = data[end..]
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .map_or(data.len(), |i| end + i + 1);
+    let header = String::from_utf8_lossy(&data[..end]);
+    let mut lines = header.lines();
+    if lines.next().map(str::trim) != Some("ply") {
+        return Err("Not a PLY file".to_string());
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    for line in lines {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens[..] {
+            ["format", name, _] => {
+                format = Some(match name {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::BinaryLittleEndian,
+                    "binary_big_endian" => Format::BinaryBigEndian,
+                    _ => return Err(format!("Unknown PLY format \"{}\"", name)),
+                })
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| format!("Invalid element count \"{}\"", count))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => {
+                let kind = match (Scalar::parse(count), Scalar::parse(item)) {
+                    (Some(count), Some(item)) => PropertyType::List(count, item),
+                    _ => return Err(format!("Unknown type of property \"{}\"", name)),
+                };
+                add_property(&mut elements, name, kind)?;
+            }
+            ["property", scalar, name] => {
+                let kind = Scalar::parse(scalar)
+                    .map(PropertyType::Scalar)
+                    .ok_or_else(|| format!("Unknown type of property \"{}\"", name))?;
+                add_property(&mut elements, name, kind)?;
+            }
+            _ => {}
+        }
+    }
+    let format = format.ok_or_else(|| "PLY file has no format".to_string())?;
+    Ok((format, elements, body))
+}
+
+fn add_property(elements: &mut [Element], name: &str, kind: PropertyType) -> Result<(), String> {
+    let element = elements
+        .last_mut()
+        .ok_or_else(|| format!("Property \"{}\" is not in an element", name))?;
+    element.properties.push(Property {
+        name: name.to_string(),
+        kind,
+    });
+    Ok(())
+}
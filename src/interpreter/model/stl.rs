@@ -0,0 +1,88 @@
+use super::{parse_vec3, Mesh, Parts};
+use crate::tracer::{material::MaterialEnum, vec3::Vec3};
+use std::path::Path;
+
+/// Header, triangle count, and per triangle a normal, 3 vertices and an attribute count.
+const BINARY_HEADER_LEN: usize = 84;
+const BINARY_TRIANGLE_LEN: usize = 50;
+
+/// Flat triangles of a binary or ASCII STL file. Facet normals are not used since they are
+/// often missing and the vertex order gives the same orientation.
+pub(super) fn load(
+    _path: &Path,
+    data: &[u8],
+    material: Option<&MaterialEnum>,
+) -> Result<Parts, String> {
+    let material = material
+        .ok_or_else(|| "STL files have no materials; give the Model a material".to_string())?;
+    let vertices = if is_binary(data) {
+        binary_vertices(data)
+    } else {
+        ascii_vertices(&String::from_utf8_lossy(data))?
+    };
+    let faces = (0..vertices.len() / 3)
+        .map(|i| [3 * i, 3 * i + 1, 3 * i + 2])
+        .collect();
+    let mesh = Mesh {
+        vertices,
+        faces,
+        normals: None,
+        uvs: None,
+        colors: None,
+    };
+    Ok(vec![(mesh, Some(material.clone()))])
+}
+
+/// Binary files may also start with "solid", so they are recognized by their size.
+fn is_binary(data: &[u8]) -> bool {
+    if data.len() < BINARY_HEADER_LEN {
+        return false;
+    }
+    let count = u32::from_le_bytes(data[80..84].try_into().unwrap()) as usize;
+    data.len() == BINARY_HEADER_LEN + count * BINARY_TRIANGLE_LEN
+}
+
+fn binary_vertices(data: &[u8]) -> Vec<Vec3> {
+    let float = |bytes: &[u8], offset: usize| {
+        f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()) as f64
+    };
+    data[BINARY_HEADER_LEN..]
+        .chunks_exact(BINARY_TRIANGLE_LEN)
+        .flat_map(|triangle| {
+            // the vertices follow the normal
+            (1..4).map(move |corner| {
+                let offset = corner * 12;
+                Vec3::new(
+                    float(triangle, offset),
+                    float(triangle, offset + 4),
+                    float(triangle, offset + 8),
+                )
+            })
+        })
+        .collect()
+}
+
+fn ascii_vertices(source: &str) -> Result<Vec<Vec3>, String> {
+    let mut vertices = Vec::new();
+    let mut facet_vertices = 0;
+    for (number, line) in source.lines().enumerate() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("vertex") => {
+                let args: Vec<&str> = tokens.collect();
+                let vertex =
+                    parse_vec3(&args).map_err(|e| format!("line {}: {}", number + 1, e))?;
+                vertices.push(vertex);
+                facet_vertices += 1;
+            }
+            Some("endfacet") => {
+                if facet_vertices != 3 {
+                    return Err(format!("line {}: A facet needs 3 vertices", number + 1));
+                }
+                facet_vertices = 0;
+            }
+            _ => {}
+        }
+    }
+    Ok(vertices)
+}
@@ -1,34 +1,299 @@
-use super::{expression::eval_expr, funcs::Functions, value::Value, EvalError, Variables};
+use super::{
+    expression::{eval_exprs, next_arg},
+    field,
+    funcs::Functions,
+    height_field::Heights,
+    limits::Budget,
+    mesh::{self, Mesh},
+    model, patch, sweep,
+    transform::Affine,
+    value::Value,
+    EvalError, Variables, COLOR_MAX,
+};
 use crate::ast::{
-    object::{AffineProperties, RotateAxis},
-    Object,
+    object::{
+        AffineProperties, CsgOperation, HeightSource, Interpolation, PatchSource, QuadShape,
+        RotateAxis, SweepProfile,
+    },
+    Expression, Object,
 };
-use ray_tracer_rs::{
+use crate::tracer::{
     hittable::{
-        BvhNode, Cuboid, HittableEnum, RotateX, RotateY, RotateZ, Sphere, Translation, XYRect,
-        XZRect, YZRect,
+        Blob, BlobComponent, BvhNode, Cone, ConstantMedium, Csg, Cuboid, Cylinder, Disk,
+        HittableEnum, Instance, Isosurface, MovingTranslation, Quad, Sphere, Torus, Transform,
+        Triangle, XYRect, XZRect, YZRect,
     },
-    vec3::Vec3,
+    vec3::{cross, Color, Vec3},
 };
 
-use std::boxed::Box;
+use std::{boxed::Box, sync::Arc};
 
 pub(super) fn eval_object<'a>(
     object: &'a Object,
     variables: &mut Variables,
     funcs: &Functions,
     world: &mut Vec<HittableEnum>,
+    budget: &mut Budget<'a>,
 ) -> Result<(), EvalError<'a>> {
-    let (mut obj, affine): (HittableEnum, &Vec<AffineProperties>) = match object {
+    let mut children = Vec::new();
+    for obj in object.children().iter() {
+        eval_object(obj, variables, funcs, &mut children, budget)?;
+    }
+    budget.object().map_err(|message| EvalError {
+        span: budget.error_span(None),
+        message,
+    })?;
+    let args = eval_exprs(object_exprs(object), variables, funcs)?;
+    world.push(build_object(object, args, children, budget)?);
+    Ok(())
+}
+
+/// Expressions an object reads, in the order `build_object` consumes their values.
+/// Children (see `Object::children`) are not included since they are built on their own.
+pub(super) fn object_exprs<'a>(object: &'a Object<'a>) -> Vec<&'a Expression<'a>> {
+    let (mut exprs, affine): (Vec<&Expression>, _) = match object {
         Object::Sphere {
             center,
             radius,
             material,
+            center_end,
+            affine,
+        } => {
+            let mut exprs: Vec<&Expression> = vec![center, radius, material];
+            exprs.extend(center_end.as_ref());
+            (exprs, affine)
+        }
+        Object::Blob {
+            threshold,
+            components,
+            material,
+            affine,
+        } => {
+            let mut exprs: Vec<&Expression> = vec![threshold];
+            for component in components.iter() {
+                exprs.extend([&component.center, &component.radius, &component.strength]);
+            }
+            exprs.push(material);
+            (exprs, affine)
+        }
+        Object::Box {
+            vertex,
+            material,
+            affine,
+        }
+        | Object::Plane {
+            vertex,
+            material,
+            affine,
+        } => (vec![&vertex.0, &vertex.1, material], affine),
+        Object::Quad {
+            origin,
+            u,
+            v,
+            material,
+            affine,
+            ..
+        } => (vec![origin, u, v, material], affine),
+        Object::Triangle {
+            vertex,
+            material,
+            affine,
+        } => (vec![&vertex.0, &vertex.1, &vertex.2, material], affine),
+        Object::Mesh {
+            vertices,
+            faces,
+            normals,
+            uvs,
+            material,
+            affine,
+        } => {
+            let mut exprs: Vec<&Expression> = vec![vertices, faces];
+            exprs.extend(normals.as_ref());
+            exprs.extend(uvs.as_ref());
+            exprs.push(material);
+            (exprs, affine)
+        }
+        Object::Cylinder {
+            base,
+            cap,
+            radius,
+            material,
+            affine,
+            ..
+        } => (vec![base, cap, radius, material], affine),
+        Object::Cone {
+            base,
+            base_radius,
+            cap,
+            cap_radius,
+            material,
+            affine,
+        } => (vec![base, base_radius, cap, cap_radius, material], affine),
+        Object::Disk {
+            center,
+            normal,
+            radius,
+            material,
+            affine,
+        } => (vec![center, normal, radius, material], affine),
+        Object::Torus {
+            major,
+            minor,
+            material,
+            affine,
+        } => (vec![major, minor, material], affine),
+        Object::Model {
+            file,
+            material,
+            affine,
+            ..
+        } => {
+            let mut exprs: Vec<&Expression> = vec![file];
+            exprs.extend(material.as_ref());
+            (exprs, affine)
+        }
+        Object::Objects { affine, .. } | Object::Csg { affine, .. } => (vec![], affine),
+        Object::HeightField {
+            source,
+            size,
+            material,
+            affine,
+        } => {
+            // the function is evaluated per point while building, with the variables it reads
+            let mut exprs: Vec<&Expression> = match source {
+                HeightSource::Image(file) => vec![file],
+                HeightSource::Function {
+                    function,
+                    resolution,
+                } => {
+                    let mut exprs: Vec<&Expression> = resolution.iter().collect();
+                    exprs.extend(
+                        field::variables(function, &["x", "z"])
+                            .into_iter()
+                            .map(|(_, expr)| expr),
+                    );
+                    exprs
+                }
+            };
+            exprs.extend(size.as_ref());
+            exprs.push(material);
+            (exprs, affine)
+        }
+        Object::Lathe {
+            points,
+            segments,
+            material,
+            affine,
+            ..
+        } => {
+            let mut exprs: Vec<&Expression> = vec![points];
+            exprs.extend(segments.as_ref());
+            exprs.push(material);
+            (exprs, affine)
+        }
+        Object::Sweep {
+            path,
+            profile,
+            material,
+            affine,
+            ..
+        } => {
+            let mut exprs: Vec<&Expression> = vec![path];
+            match profile {
+                SweepProfile::Points(points) => exprs.push(points),
+                SweepProfile::Circle { radius, segments } => {
+                    exprs.push(radius);
+                    exprs.extend(segments.as_ref());
+                }
+            }
+            exprs.push(material);
+            (exprs, affine)
+        }
+        Object::BezierPatch {
+            source,
+            steps,
+            material,
             affine,
         } => {
-            let center_val = eval_expr(center, variables, funcs)?;
-            let radius = eval_expr(radius, variables, funcs)?;
-            let (center, radius) = match (center_val, radius) {
+            let mut exprs: Vec<&Expression> = match source {
+                PatchSource::Control(control) => vec![control],
+                PatchSource::File(file) => vec![file],
+            };
+            exprs.extend(steps.as_ref());
+            exprs.push(material);
+            (exprs, affine)
+        }
+        Object::Isosurface {
+            function,
+            bounds,
+            threshold,
+            max_gradient,
+            material,
+            affine,
+        } => {
+            // the function is compiled, not evaluated, but the variables it reads are
+            let mut exprs: Vec<&Expression> = vec![&bounds.0, &bounds.1];
+            exprs.extend(threshold.as_ref());
+            exprs.extend(max_gradient.as_ref());
+            exprs.push(material);
+            exprs.extend(
+                field::variables(function, &["x", "y", "z"])
+                    .into_iter()
+                    .map(|(_, expr)| expr),
+            );
+            (exprs, affine)
+        }
+        Object::Instance {
+            of,
+            material,
+            affine,
+        } => {
+            let mut exprs: Vec<&Expression> = vec![of];
+            exprs.extend(material.as_ref());
+            (exprs, affine)
+        }
+        Object::Volume {
+            density,
+            color,
+            noise,
+            affine,
+            ..
+        } => {
+            let mut exprs: Vec<&Expression> = vec![density, color];
+            exprs.extend(noise.as_ref());
+            (exprs, affine)
+        }
+    };
+    for af in affine.iter() {
+        match af {
+            AffineProperties::Translation(expr) => exprs.push(expr),
+            AffineProperties::Rotate(rotate) => exprs.push(&rotate.expr),
+            AffineProperties::AxisRotate { axis, angle } => exprs.extend([axis, angle]),
+            AffineProperties::Scale(expr)
+            | AffineProperties::Matrix(expr)
+            | AffineProperties::Pivot(expr)
+            | AffineProperties::Motion(expr) => exprs.push(expr),
+        }
+    }
+    exprs
+}
+
+pub(super) fn build_object<'a>(
+    object: &'a Object<'a>,
+    args: Vec<Value>,
+    mut children: Vec<HittableEnum>,
+    budget: &mut Budget<'a>,
+) -> Result<HittableEnum, EvalError<'a>> {
+    let mut args = args.into_iter();
+    let (mut obj, affine): (HittableEnum, &Vec<AffineProperties>) = match object {
+        Object::Sphere {
+            center,
+            material,
+            center_end,
+            affine,
+            ..
+        } => {
+            let (center, radius) = match (next_arg(&mut args), next_arg(&mut args)) {
                 (Value::Vec3(x, y, z), Value::Num(num)) => (Vec3::new(x, y, z), num),
                 _ => {
                     return Err(EvalError {
@@ -37,8 +302,7 @@ pub(super) fn eval_object<'a>(
                     });
                 }
             };
-            let material_val = eval_expr(material, variables, funcs)?;
-            let material = match material_val {
+            let material = match next_arg(&mut args) {
                 Value::Material(material) => material,
                 _ => {
                     return Err(EvalError {
@@ -47,8 +311,80 @@ pub(super) fn eval_object<'a>(
                     });
                 }
             };
+            let sphere = HittableEnum::Sphere(Sphere::new(&center, radius, material));
+            let sphere = match center_end.as_ref() {
+                Some(expr) => {
+                    let motion = match next_arg(&mut args) {
+                        Value::Vec3(x, y, z) => Vec3::new(x, y, z) - center,
+                        _ => {
+                            return Err(EvalError {
+                                span: Some(expr.span),
+                                message: "Invalid center_end for Sphere".to_string(),
+                            });
+                        }
+                    };
+                    moving(sphere, motion)
+                }
+                None => sphere,
+            };
+            (sphere, affine)
+        }
+        Object::Blob {
+            threshold,
+            components,
+            material,
+            affine,
+        } => {
+            let threshold_value = match next_arg(&mut args) {
+                Value::Num(n) if n > 0.0 => n,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(threshold.span),
+                        message: "Blob threshold must be a positive number".to_string(),
+                    });
+                }
+            };
+            let mut blob_components = Vec::with_capacity(components.len());
+            for component in components.iter() {
+                let (center, radius, strength) = match (
+                    next_arg(&mut args),
+                    next_arg(&mut args),
+                    next_arg(&mut args),
+                ) {
+                    (Value::Vec3(x, y, z), Value::Num(radius), Value::Num(strength)) => {
+                        (Vec3::new(x, y, z), radius, strength)
+                    }
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(component.center.span),
+                            message: "Invalid center, radius or strength for Blob component"
+                                .to_string(),
+                        });
+                    }
+                };
+                if radius <= 0.0 {
+                    return Err(EvalError {
+                        span: Some(component.radius.span),
+                        message: "Blob component radius must be positive".to_string(),
+                    });
+                }
+                blob_components.push(BlobComponent::new(&center, radius, strength));
+            }
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for Blob".to_string(),
+                    });
+                }
+            };
             (
-                HittableEnum::Sphere(Sphere::new(&center, radius, material)),
+                HittableEnum::Blob(Box::new(Blob::new(
+                    blob_components,
+                    threshold_value,
+                    material,
+                ))),
                 affine,
             )
         }
@@ -57,11 +393,9 @@ pub(super) fn eval_object<'a>(
             material,
             affine,
         } => {
-            let vertex1 = eval_expr(&vertex.0, variables, funcs)?;
-            let vertex2 = eval_expr(&vertex.1, variables, funcs)?;
-            let (vertex1, vertex2) = match (vertex1, vertex2) {
+            let (vertex1, vertex2) = match (next_arg(&mut args), next_arg(&mut args)) {
                 (Value::Vec3(x1, y1, z1), Value::Vec3(x2, y2, z2)) => {
-                    if (x1 == x2 || y1 == y2 || z1 == z2) {
+                    if x1 == x2 || y1 == y2 || z1 == z2 {
                         return Err(EvalError {
                             span: Some(vertex.0.span),
                             message: "Box vertexes should be completely different".to_string(),
@@ -76,8 +410,7 @@ pub(super) fn eval_object<'a>(
                     });
                 }
             };
-            let material_val = eval_expr(material, variables, funcs)?;
-            let material = match material_val {
+            let material = match next_arg(&mut args) {
                 Value::Material(material) => material,
                 _ => {
                     return Err(EvalError {
@@ -96,10 +429,7 @@ pub(super) fn eval_object<'a>(
             material,
             affine,
         } => {
-            let (vertex1, vertex2) = match (
-                eval_expr(&vertex.0, variables, funcs)?,
-                eval_expr(&vertex.1, variables, funcs)?,
-            ) {
+            let (vertex1, vertex2) = match (next_arg(&mut args), next_arg(&mut args)) {
                 (Value::Vec3(x1, y1, z1), Value::Vec3(x2, y2, z2)) => {
                     (Vec3::new(x1, y1, z1), Vec3::new(x2, y2, z2))
                 }
@@ -110,7 +440,7 @@ pub(super) fn eval_object<'a>(
                     });
                 }
             };
-            let material = match eval_expr(material, variables, funcs)? {
+            let material = match next_arg(&mut args) {
                 Value::Material(material) => material,
                 _ => {
                     return Err(EvalError {
@@ -157,50 +487,1108 @@ pub(super) fn eval_object<'a>(
             };
             (rect, affine)
         }
-        Object::Objects { objects, affine } => {
-            let mut objs = Vec::new();
-            for obj in objects.iter() {
-                eval_object(obj, variables, funcs, &mut objs)?;
+        Object::Quad {
+            origin,
+            u,
+            shape,
+            material,
+            affine,
+            ..
+        } => {
+            let (q, edge_u, edge_v) = match (
+                next_arg(&mut args),
+                next_arg(&mut args),
+                next_arg(&mut args),
+            ) {
+                (Value::Vec3(x, y, z), Value::Vec3(ux, uy, uz), Value::Vec3(vx, vy, vz)) => (
+                    Vec3::new(x, y, z),
+                    Vec3::new(ux, uy, uz),
+                    Vec3::new(vx, vy, vz),
+                ),
+                _ => {
+                    return Err(EvalError {
+                        span: Some(origin.span),
+                        message: "Invalid origin, u or v for Quad".to_string(),
+                    });
+                }
+            };
+            if cross(&edge_u, &edge_v).length() == 0.0 {
+                return Err(EvalError {
+                    span: Some(u.span),
+                    message: "Quad edges u and v must not be parallel".to_string(),
+                });
+            }
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for Quad".to_string(),
+                    });
+                }
+            };
+            let quad = match shape {
+                QuadShape::Parallelogram => {
+                    HittableEnum::Quad(Quad::new(&q, &edge_u, &edge_v, material))
+                }
+                QuadShape::Triangle => HittableEnum::Triangle(Triangle::new(
+                    &q,
+                    &(q + edge_u),
+                    &(q + edge_v),
+                    material,
+                )),
+            };
+            (quad, affine)
+        }
+        Object::Triangle {
+            vertex,
+            material,
+            affine,
+        } => {
+            let (a, b, c) = match (
+                next_arg(&mut args),
+                next_arg(&mut args),
+                next_arg(&mut args),
+            ) {
+                (Value::Vec3(ax, ay, az), Value::Vec3(bx, by, bz), Value::Vec3(cx, cy, cz)) => (
+                    Vec3::new(ax, ay, az),
+                    Vec3::new(bx, by, bz),
+                    Vec3::new(cx, cy, cz),
+                ),
+                _ => {
+                    return Err(EvalError {
+                        span: Some(vertex.0.span),
+                        message: "Invalid vertex for Triangle".to_string(),
+                    });
+                }
+            };
+            if cross(&(b - a), &(c - a)).length() == 0.0 {
+                return Err(EvalError {
+                    span: Some(vertex.0.span),
+                    message: "Triangle vertexes must not be on one line".to_string(),
+                });
             }
-            // TODO: apply motion blur
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for Triangle".to_string(),
+                    });
+                }
+            };
             (
-                HittableEnum::BvhNode(Box::new(BvhNode::new(&mut objs, 0.0, 0.0))),
+                HittableEnum::Triangle(Triangle::new(&a, &b, &c, material)),
                 affine,
             )
         }
-    };
-    for af in affine.iter() {
-        match af {
-            AffineProperties::Translation(expr) => {
-                let offset = match eval_expr(expr, variables, funcs)? {
-                    Value::Vec3(x, y, z) => Vec3::new(x, y, z),
+        Object::Mesh {
+            vertices,
+            faces,
+            normals,
+            uvs,
+            material,
+            affine,
+        } => {
+            let vertices_value = mesh::vec3_array(next_arg(&mut args)).ok_or(EvalError {
+                span: Some(vertices.span),
+                message: "vertices must be an array of Vec3".to_string(),
+            })?;
+            let vertex_count = vertices_value.len();
+            let faces_value =
+                mesh::faces(next_arg(&mut args), vertex_count).map_err(|message| EvalError {
+                    span: Some(faces.span),
+                    message,
+                })?;
+            let normals_value = match normals.as_ref() {
+                Some(expr) => match mesh::vec3_array(next_arg(&mut args)) {
+                    Some(normals) if normals.len() == vertex_count => Some(normals),
                     _ => {
                         return Err(EvalError {
                             span: Some(expr.span),
-                            message: "Invalid arguments for Translation".to_string(),
-                        })
+                            message: "normals must be an array of one Vec3 per vertex".to_string(),
+                        });
                     }
-                };
-                obj = HittableEnum::Translation(Translation::new(obj, offset));
-            }
-            AffineProperties::Rotate(rotate) => {
-                let angle = match eval_expr(&rotate.expr, variables, funcs)? {
-                    Value::Num(num) => num,
+                },
+                None => None,
+            };
+            let uvs_value = match uvs.as_ref() {
+                Some(expr) => match mesh::uv_array(next_arg(&mut args)) {
+                    Some(uvs) if uvs.len() == vertex_count => Some(uvs),
                     _ => {
                         return Err(EvalError {
-                            span: Some(rotate.expr.span),
-                            message: "Invalid arguments for Rotate".to_string(),
-                        })
+                            span: Some(expr.span),
+                            message: "uvs must be an array of one [u, v] per vertex".to_string(),
+                        });
                     }
-                };
-                obj = match rotate.axis {
-                    RotateAxis::X => HittableEnum::RotateX(Box::new(RotateX::new(obj, angle))),
-                    RotateAxis::Y => HittableEnum::RotateY(Box::new(RotateY::new(obj, angle))),
-                    RotateAxis::Z => HittableEnum::RotateZ(Box::new(RotateZ::new(obj, angle))),
-                };
+                },
+                None => None,
+            };
+            let material_value = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for Mesh".to_string(),
+                    });
+                }
+            };
+            let mesh = Mesh {
+                vertices: vertices_value,
+                faces: faces_value,
+                normals: normals_value,
+                uvs: uvs_value,
+                colors: None,
+            };
+            let mesh = mesh
+                .build(Some(material_value))
+                .map_err(|message| EvalError {
+                    span: Some(faces.span),
+                    message,
+                })?;
+            (mesh, affine)
+        }
+        Object::Cylinder {
+            base,
+            radius,
+            open,
+            material,
+            affine,
+            ..
+        } => {
+            let (base_point, cap_point, radius_value) = match (
+                next_arg(&mut args),
+                next_arg(&mut args),
+                next_arg(&mut args),
+            ) {
+                (Value::Vec3(x1, y1, z1), Value::Vec3(x2, y2, z2), Value::Num(r)) => {
+                    (Vec3::new(x1, y1, z1), Vec3::new(x2, y2, z2), r)
+                }
+                _ => {
+                    return Err(EvalError {
+                        span: Some(base.span),
+                        message: "Invalid base, cap or radius for Cylinder".to_string(),
+                    });
+                }
+            };
+            if base_point == cap_point {
+                return Err(EvalError {
+                    span: Some(base.span),
+                    message: "Cylinder base and cap must be different".to_string(),
+                });
+            }
+            if radius_value <= 0.0 {
+                return Err(EvalError {
+                    span: Some(radius.span),
+                    message: "Cylinder radius must be positive".to_string(),
+                });
+            }
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for Cylinder".to_string(),
+                    });
+                }
+            };
+            (
+                HittableEnum::Cylinder(Cylinder::new(
+                    &base_point,
+                    &cap_point,
+                    radius_value,
+                    *open,
+                    material,
+                )),
+                affine,
+            )
+        }
+        Object::Cone {
+            base,
+            base_radius,
+            material,
+            affine,
+            ..
+        } => {
+            let (base_point, base_radius_value, cap_point, cap_radius_value) = match (
+                next_arg(&mut args),
+                next_arg(&mut args),
+                next_arg(&mut args),
+                next_arg(&mut args),
+            ) {
+                (
+                    Value::Vec3(x1, y1, z1),
+                    Value::Num(r1),
+                    Value::Vec3(x2, y2, z2),
+                    Value::Num(r2),
+                ) => (Vec3::new(x1, y1, z1), r1, Vec3::new(x2, y2, z2), r2),
+                _ => {
+                    return Err(EvalError {
+                        span: Some(base.span),
+                        message: "Invalid base, cap or radius for Cone".to_string(),
+                    });
+                }
+            };
+            if base_point == cap_point {
+                return Err(EvalError {
+                    span: Some(base.span),
+                    message: "Cone base and cap must be different".to_string(),
+                });
+            }
+            if base_radius_value < 0.0
+                || cap_radius_value < 0.0
+                || base_radius_value + cap_radius_value == 0.0
+            {
+                return Err(EvalError {
+                    span: Some(base_radius.span),
+                    message: "Cone radii must not be negative or both 0".to_string(),
+                });
             }
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for Cone".to_string(),
+                    });
+                }
+            };
+            (
+                HittableEnum::Cone(Cone::new(
+                    &base_point,
+                    base_radius_value,
+                    &cap_point,
+                    cap_radius_value,
+                    material,
+                )),
+                affine,
+            )
         }
-    }
-    world.push(obj);
-    Ok(())
+        Object::Disk {
+            center,
+            normal,
+            radius,
+            material,
+            affine,
+        } => {
+            let (center_point, normal_vector, radius_value) = match (
+                next_arg(&mut args),
+                next_arg(&mut args),
+                next_arg(&mut args),
+            ) {
+                (Value::Vec3(x1, y1, z1), Value::Vec3(x2, y2, z2), Value::Num(r)) => {
+                    (Vec3::new(x1, y1, z1), Vec3::new(x2, y2, z2), r)
+                }
+                _ => {
+                    return Err(EvalError {
+                        span: Some(center.span),
+                        message: "Invalid center, normal or radius for Disk".to_string(),
+                    });
+                }
+            };
+            if normal_vector.length() == 0.0 {
+                return Err(EvalError {
+                    span: Some(normal.span),
+                    message: "Disk normal must not be <0, 0, 0>".to_string(),
+                });
+            }
+            if radius_value <= 0.0 {
+                return Err(EvalError {
+                    span: Some(radius.span),
+                    message: "Disk radius must be positive".to_string(),
+                });
+            }
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for Disk".to_string(),
+                    });
+                }
+            };
+            (
+                HittableEnum::Disk(Disk::new(
+                    &center_point,
+                    &normal_vector,
+                    radius_value,
+                    material,
+                )),
+                affine,
+            )
+        }
+        Object::Torus {
+            major,
+            material,
+            affine,
+            ..
+        } => {
+            let (major_radius, minor_radius) = match (next_arg(&mut args), next_arg(&mut args)) {
+                (Value::Num(major), Value::Num(minor)) if major > 0.0 && minor > 0.0 => {
+                    (major, minor)
+                }
+                _ => {
+                    return Err(EvalError {
+                        span: Some(major.span),
+                        message: "Torus major and minor radii must be positive numbers".to_string(),
+                    });
+                }
+            };
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for Torus".to_string(),
+                    });
+                }
+            };
+            (
+                HittableEnum::Torus(Torus::new(major_radius, minor_radius, material)),
+                affine,
+            )
+        }
+        Object::Model {
+            file,
+            material,
+            normalize,
+            affine,
+        } => {
+            let path = match next_arg(&mut args) {
+                Value::Str(path) => path,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(file.span),
+                        message: "Invalid file for Model".to_string(),
+                    });
+                }
+            };
+            let material = match material.as_ref() {
+                Some(expr) => match next_arg(&mut args) {
+                    Value::Material(material) => Some(material),
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(expr.span),
+                            message: "Invalid material for Model".to_string(),
+                        });
+                    }
+                },
+                None => None,
+            };
+            // reported at the statement, since the problem is in the file
+            let model =
+                model::load_model(&path, material, *normalize).map_err(|message| EvalError {
+                    span: None,
+                    message,
+                })?;
+            (model, affine)
+        }
+        Object::Objects { affine, .. } => (
+            HittableEnum::BvhNode(Box::new(BvhNode::new(
+                &mut children,
+                MOTION_START,
+                MOTION_END,
+            ))),
+            affine,
+        ),
+        Object::Csg {
+            operation,
+            objects,
+            affine,
+        } => {
+            let name = match operation {
+                CsgOperation::Difference => "Difference",
+                CsgOperation::Intersection => "Intersection",
+                CsgOperation::Merge => "Merge",
+            };
+            // reported at the statement, since the children have no span of their own
+            if *operation != CsgOperation::Merge && objects.len() < 2 {
+                return Err(EvalError {
+                    span: None,
+                    message: format!("{} needs at least 2 objects", name),
+                });
+            }
+            if !objects.iter().all(is_closed) {
+                return Err(EvalError {
+                    span: None,
+                    message: format!("{} needs closed objects: {}", name, CLOSED_OBJECTS),
+                });
+            }
+            let combine = match operation {
+                CsgOperation::Difference => Csg::difference,
+                CsgOperation::Intersection => Csg::intersection,
+                CsgOperation::Merge => Csg::merge,
+            };
+            let csg = children
+                .into_iter()
+                .reduce(|a, b| HittableEnum::Csg(Box::new(combine(a, b))))
+                .unwrap();
+            (csg, affine)
+        }
+        Object::HeightField {
+            source,
+            size,
+            material,
+            affine,
+        } => {
+            let heights = match source {
+                HeightSource::Image(file) => {
+                    let path = match next_arg(&mut args) {
+                        Value::Str(path) => path,
+                        _ => {
+                            return Err(EvalError {
+                                span: Some(file.span),
+                                message: "Invalid image for HeightField".to_string(),
+                            });
+                        }
+                    };
+                    Heights::from_image(&path).map_err(|message| EvalError {
+                        span: Some(file.span),
+                        message,
+                    })?
+                }
+                HeightSource::Function {
+                    function,
+                    resolution,
+                } => {
+                    let points = match resolution.as_ref() {
+                        Some(expr) => match next_arg(&mut args) {
+                            Value::Num(n)
+                                if n.fract() == 0.0
+                                    && (2.0..=MAX_HEIGHT_FIELD_RESOLUTION as f64).contains(&n) =>
+                            {
+                                n as usize
+                            }
+                            _ => {
+                                return Err(EvalError {
+                                    span: Some(expr.span),
+                                    message: format!(
+                                        "HeightField resolution must be a whole number from 2 to {}",
+                                        MAX_HEIGHT_FIELD_RESOLUTION
+                                    ),
+                                });
+                            }
+                        },
+                        None => DEFAULT_HEIGHT_FIELD_RESOLUTION,
+                    };
+                    let mut constants = Vec::new();
+                    for (name, variable) in field::variables(function, &["x", "z"]) {
+                        match next_arg(&mut args) {
+                            Value::Num(n) => constants.push((name, n)),
+                            _ => {
+                                return Err(EvalError {
+                                    span: Some(variable.span),
+                                    message: format!(
+                                        "HeightField function can only use numeric variables, not \"{}\"",
+                                        name
+                                    ),
+                                });
+                            }
+                        }
+                    }
+                    Heights::from_function(function, points, &constants, budget)?
+                }
+            };
+            let size_value = match size.as_ref() {
+                Some(expr) => match next_arg(&mut args) {
+                    Value::Vec3(x, y, z) if x > 0.0 && z > 0.0 => Vec3::new(x, y, z),
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(expr.span),
+                            message: "HeightField size must be a Vec3 with positive x and z"
+                                .to_string(),
+                        });
+                    }
+                },
+                None => Vec3::new(1.0, 1.0, 1.0),
+            };
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for HeightField".to_string(),
+                    });
+                }
+            };
+            // reported at the statement, since the problem is in the heights
+            let field = heights
+                .mesh(size_value)
+                .build(Some(material))
+                .map_err(|message| EvalError {
+                    span: None,
+                    message,
+                })?;
+            (field, affine)
+        }
+        Object::Lathe {
+            points,
+            spline,
+            segments,
+            material,
+            affine,
+        } => {
+            let points_value = match mesh::uv_array(next_arg(&mut args)) {
+                Some(points) if points.len() >= 2 && points.iter().all(|(r, _)| *r >= 0.0) => {
+                    points
+                }
+                _ => {
+                    return Err(EvalError {
+                        span: Some(points.span),
+                        message:
+                            "Lathe points must be an array of at least 2 [r, y] with r not negative"
+                                .to_string(),
+                    });
+                }
+            };
+            let segments_value = match segments.as_ref() {
+                Some(expr) => segment_count(next_arg(&mut args)).ok_or_else(|| EvalError {
+                    span: Some(expr.span),
+                    message: format!(
+                        "Lathe segments must be a whole number from 3 to {}",
+                        MAX_SEGMENTS
+                    ),
+                })?,
+                None => DEFAULT_LATHE_SEGMENTS,
+            };
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for Lathe".to_string(),
+                    });
+                }
+            };
+            let cubic = *spline == Interpolation::Cubic;
+            let lathe = sweep::lathe(&points_value, cubic, segments_value)
+                .build(Some(material))
+                .map_err(|message| EvalError {
+                    span: Some(points.span),
+                    message,
+                })?;
+            (lathe, affine)
+        }
+        Object::Sweep {
+            path,
+            profile,
+            spline,
+            material,
+            affine,
+        } => {
+            let path_value = match mesh::vec3_array(next_arg(&mut args)) {
+                Some(path) if path.len() >= 2 => path,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(path.span),
+                        message: "Sweep path must be an array of at least 2 Vec3".to_string(),
+                    });
+                }
+            };
+            let profile_value = match profile {
+                SweepProfile::Points(points) => match mesh::uv_array(next_arg(&mut args)) {
+                    Some(points) if points.len() >= 3 => points,
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(points.span),
+                            message: "Sweep profile must be an array of at least 3 [x, y]"
+                                .to_string(),
+                        });
+                    }
+                },
+                SweepProfile::Circle { radius, segments } => {
+                    let radius_value = match next_arg(&mut args) {
+                        Value::Num(n) if n > 0.0 => n,
+                        _ => {
+                            return Err(EvalError {
+                                span: Some(radius.span),
+                                message: "Sweep radius must be a positive number".to_string(),
+                            });
+                        }
+                    };
+                    let segments_value = match segments.as_ref() {
+                        Some(expr) => {
+                            segment_count(next_arg(&mut args)).ok_or_else(|| EvalError {
+                                span: Some(expr.span),
+                                message: format!(
+                                    "Sweep segments must be a whole number from 3 to {}",
+                                    MAX_SEGMENTS
+                                ),
+                            })?
+                        }
+                        None => DEFAULT_SWEEP_SEGMENTS,
+                    };
+                    sweep::circle(radius_value, segments_value)
+                }
+            };
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for Sweep".to_string(),
+                    });
+                }
+            };
+            let cubic = *spline == Interpolation::Cubic;
+            let sweep = sweep::sweep(&path_value, &profile_value, cubic)
+                .and_then(|mesh| mesh.build(Some(material)))
+                .map_err(|message| EvalError {
+                    span: Some(path.span),
+                    message,
+                })?;
+            (sweep, affine)
+        }
+        Object::BezierPatch {
+            source,
+            steps,
+            material,
+            affine,
+        } => {
+            let patches = match source {
+                PatchSource::Control(control) => {
+                    match patch::Patch::from_control(next_arg(&mut args)) {
+                        Some(patch) => vec![patch],
+                        None => {
+                            return Err(EvalError {
+                            span: Some(control.span),
+                            message: "BezierPatch control must be an array of 16 Vec3 or of 4 rows of 4 Vec3".to_string(),
+                        });
+                        }
+                    }
+                }
+                PatchSource::File(file) => {
+                    let path = match next_arg(&mut args) {
+                        Value::Str(path) => path,
+                        _ => {
+                            return Err(EvalError {
+                                span: Some(file.span),
+                                message: "Invalid file for BezierPatch".to_string(),
+                            });
+                        }
+                    };
+                    patch::load(&path).map_err(|message| EvalError {
+                        span: Some(file.span),
+                        message,
+                    })?
+                }
+            };
+            let steps_value = match steps.as_ref() {
+                Some(expr) => match next_arg(&mut args) {
+                    Value::Num(n)
+                        if n.fract() == 0.0 && (1.0..=MAX_PATCH_STEPS as f64).contains(&n) =>
+                    {
+                        n as usize
+                    }
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(expr.span),
+                            message: format!(
+                                "BezierPatch steps must be a whole number from 1 to {}",
+                                MAX_PATCH_STEPS
+                            ),
+                        });
+                    }
+                },
+                None => DEFAULT_PATCH_STEPS,
+            };
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for BezierPatch".to_string(),
+                    });
+                }
+            };
+            // reported at the statement, since the problem is in the control points
+            let patches = patch::mesh(&patches, steps_value)
+                .build(Some(material))
+                .map_err(|message| EvalError {
+                    span: None,
+                    message,
+                })?;
+            (patches, affine)
+        }
+        Object::Isosurface {
+            function,
+            bounds,
+            threshold,
+            max_gradient,
+            material,
+            affine,
+        } => {
+            let (min, max) = match (next_arg(&mut args), next_arg(&mut args)) {
+                (Value::Vec3(x1, y1, z1), Value::Vec3(x2, y2, z2))
+                    if x1 < x2 && y1 < y2 && z1 < z2 =>
+                {
+                    (Vec3::new(x1, y1, z1), Vec3::new(x2, y2, z2))
+                }
+                _ => {
+                    return Err(EvalError {
+                        span: Some(bounds.0.span),
+                        message:
+                            "Isosurface bounds must be the minimum and maximum corners of a box"
+                                .to_string(),
+                    });
+                }
+            };
+            let threshold_value = match threshold.as_ref() {
+                Some(expr) => match next_arg(&mut args) {
+                    Value::Num(n) => n,
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(expr.span),
+                            message: "Invalid threshold for Isosurface".to_string(),
+                        });
+                    }
+                },
+                None => 0.0,
+            };
+            let max_gradient_value = match max_gradient.as_ref() {
+                Some(expr) => match next_arg(&mut args) {
+                    Value::Num(n) if n > 0.0 => n,
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(expr.span),
+                            message: "Isosurface max_gradient must be a positive number"
+                                .to_string(),
+                        });
+                    }
+                },
+                None => 1.0,
+            };
+            let material = match next_arg(&mut args) {
+                Value::Material(material) => material,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(material.span),
+                        message: "Invalid material for Isosurface".to_string(),
+                    });
+                }
+            };
+            let mut constants = Vec::new();
+            for (name, variable) in field::variables(function, &["x", "y", "z"]) {
+                match next_arg(&mut args) {
+                    Value::Num(n) => constants.push((name, n)),
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(variable.span),
+                            message: format!(
+                                "Isosurface function can only use numeric variables, not \"{}\"",
+                                name
+                            ),
+                        });
+                    }
+                }
+            }
+            let field = field::compile(function, &constants)?;
+            (
+                HittableEnum::Isosurface(Box::new(Isosurface::new(
+                    Arc::new(move |p: &Vec3| field.eval(*p)),
+                    &min,
+                    &max,
+                    threshold_value,
+                    max_gradient_value,
+                    material,
+                ))),
+                affine,
+            )
+        }
+        Object::Instance {
+            of,
+            material,
+            affine,
+        } => {
+            let prototype = match next_arg(&mut args) {
+                Value::Prototype(prototype) => prototype,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(of.span),
+                        message: "Instance of must be a Prototype".to_string(),
+                    });
+                }
+            };
+            let material = match material.as_ref() {
+                Some(expr) => match next_arg(&mut args) {
+                    Value::Material(material) => Some(material),
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(expr.span),
+                            message: "Invalid material for Instance".to_string(),
+                        });
+                    }
+                },
+                None => None,
+            };
+            (
+                HittableEnum::Instance(Box::new(Instance::new(prototype, material))),
+                affine,
+            )
+        }
+        Object::Volume {
+            boundary,
+            density,
+            color,
+            noise,
+            affine,
+        } => {
+            // reported at the statement, since the boundary has no span of its own
+            if !is_closed(boundary) {
+                return Err(EvalError {
+                    span: None,
+                    message: format!("Volume boundary must be closed: {}", CLOSED_OBJECTS),
+                });
+            }
+            let density_value = match next_arg(&mut args) {
+                Value::Num(n) if n > 0.0 => n,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(density.span),
+                        message: "Volume density must be a positive number".to_string(),
+                    });
+                }
+            };
+            let color_value = match next_arg(&mut args) {
+                Value::Vec3(r, g, b) => Color::new(r, g, b) / COLOR_MAX,
+                _ => {
+                    return Err(EvalError {
+                        span: Some(color.span),
+                        message: "Invalid color for Volume".to_string(),
+                    });
+                }
+            };
+            let mut medium =
+                ConstantMedium::new(children.pop().unwrap(), density_value, color_value);
+            if let Some(expr) = noise.as_ref() {
+                medium = match next_arg(&mut args) {
+                    Value::Num(scale) if scale > 0.0 => medium.with_noise(scale),
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(expr.span),
+                            message: "Volume noise must be a positive number".to_string(),
+                        });
+                    }
+                };
+            }
+            (HittableEnum::ConstantMedium(Box::new(medium)), affine)
+        }
+    };
+    // consecutive transforms are collapsed into one; motion is applied on its own
+    let mut transform = Affine::identity();
+    let mut pivot = Vec3::zero();
+    for af in affine.iter() {
+        match af {
+            AffineProperties::Translation(expr) => {
+                let offset = match next_arg(&mut args) {
+                    Value::Vec3(x, y, z) => Vec3::new(x, y, z),
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(expr.span),
+                            message: "Invalid arguments for Translation".to_string(),
+                        })
+                    }
+                };
+                transform = transform.then(&Affine::translation(offset));
+            }
+            AffineProperties::Rotate(rotate) => {
+                let angle = match next_arg(&mut args) {
+                    Value::Num(num) => num,
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(rotate.expr.span),
+                            message: "Invalid arguments for Rotate".to_string(),
+                        })
+                    }
+                };
+                let axis = match rotate.axis {
+                    RotateAxis::X => Vec3::new(1.0, 0.0, 0.0),
+                    RotateAxis::Y => Vec3::new(0.0, 1.0, 0.0),
+                    RotateAxis::Z => Vec3::new(0.0, 0.0, 1.0),
+                };
+                transform = transform.then(&Affine::rotation(axis, angle).about(pivot));
+            }
+            AffineProperties::AxisRotate { axis, .. } => {
+                let (axis_vector, degrees) = match (next_arg(&mut args), next_arg(&mut args)) {
+                    (Value::Vec3(x, y, z), Value::Num(num)) => (Vec3::new(x, y, z), num),
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(axis.span),
+                            message: "Invalid axis or angle for rotate".to_string(),
+                        })
+                    }
+                };
+                if axis_vector.length() == 0.0 {
+                    return Err(EvalError {
+                        span: Some(axis.span),
+                        message: "rotate axis must not be <0, 0, 0>".to_string(),
+                    });
+                }
+                transform = transform.then(&Affine::rotation(axis_vector, degrees).about(pivot));
+            }
+            AffineProperties::Scale(expr) => {
+                let factor = match next_arg(&mut args) {
+                    Value::Num(num) => Vec3::new(num, num, num),
+                    Value::Vec3(x, y, z) => Vec3::new(x, y, z),
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(expr.span),
+                            message: "Invalid arguments for scale".to_string(),
+                        })
+                    }
+                };
+                if factor.x() * factor.y() * factor.z() == 0.0 {
+                    return Err(EvalError {
+                        span: Some(expr.span),
+                        message: "scale must not be 0 in any axis".to_string(),
+                    });
+                }
+                transform = transform.then(&Affine::scale(factor).about(pivot));
+            }
+            AffineProperties::Matrix(expr) => {
+                let matrix = affine_matrix(next_arg(&mut args)).map_err(|message| EvalError {
+                    span: Some(expr.span),
+                    message,
+                })?;
+                transform = transform.then(&matrix.about(pivot));
+            }
+            AffineProperties::Pivot(expr) => {
+                pivot = match next_arg(&mut args) {
+                    Value::Vec3(x, y, z) => Vec3::new(x, y, z),
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(expr.span),
+                            message: "Invalid arguments for pivot".to_string(),
+                        })
+                    }
+                };
+            }
+            AffineProperties::Motion(expr) => {
+                let motion = match next_arg(&mut args) {
+                    Value::Vec3(x, y, z) => Vec3::new(x, y, z),
+                    _ => {
+                        return Err(EvalError {
+                            span: Some(expr.span),
+                            message: "Invalid arguments for Motion".to_string(),
+                        })
+                    }
+                };
+                obj = moving(transformed(obj, transform), motion);
+                transform = Affine::identity();
+            }
+        }
+    }
+    Ok(transformed(obj, transform))
+}
+
+/// `obj` under `transform`, with no wrapper for the identity.
+fn transformed(obj: HittableEnum, transform: Affine) -> HittableEnum {
+    if transform == Affine::identity() {
+        return obj;
+    }
+    HittableEnum::Transform(Box::new(Transform::new(obj, transform.rows)))
+}
+
+/// A `matrix:` value: 3 rows of 4 numbers, or 4 rows with `[0, 0, 0, 1]` last.
+fn affine_matrix(value: Value) -> Result<Affine, String> {
+    let invalid = || "matrix must be an array of 3 or 4 rows of 4 numbers".to_string();
+    let Value::Array(rows) = value else {
+        return Err(invalid());
+    };
+    let rows = rows
+        .iter()
+        .map(|row| match row {
+            Value::Array(row) => match row[..] {
+                [Value::Num(a), Value::Num(b), Value::Num(c), Value::Num(d)] => Ok([a, b, c, d]),
+                _ => Err(invalid()),
+            },
+            _ => Err(invalid()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let affine = match rows[..] {
+        [r0, r1, r2] => Affine { rows: [r0, r1, r2] },
+        [r0, r1, r2, [0.0, 0.0, 0.0, 1.0]] => Affine { rows: [r0, r1, r2] },
+        [_, _, _, _] => return Err("The last row of matrix must be [0, 0, 0, 1]".to_string()),
+        _ => return Err(invalid()),
+    };
+    if affine.determinant() == 0.0 {
+        return Err("matrix must not flatten objects; its determinant is 0".to_string());
+    }
+    Ok(affine)
+}
+
+/// Points along each side of a `HeightField` function without a `resolution`.
+const DEFAULT_HEIGHT_FIELD_RESOLUTION: usize = 256;
+/// Limit of `resolution`, which keeps a field at a few million triangles.
+const MAX_HEIGHT_FIELD_RESOLUTION: usize = 2048;
+
+/// The objects of a `Prototype` under one BVH, which its instances share instead of each
+/// holding a copy.
+pub(super) fn build_prototype(mut objects: Vec<HittableEnum>) -> Value {
+    let prototype = match objects.len() {
+        1 => objects.pop().unwrap(),
+        _ => HittableEnum::BvhNode(Box::new(BvhNode::new(
+            &mut objects,
+            MOTION_START,
+            MOTION_END,
+        ))),
+    };
+    Value::Prototype(Arc::new(prototype))
+}
+
+/// Quads along each side of a `BezierPatch` without `steps`.
+const DEFAULT_PATCH_STEPS: usize = 8;
+/// Limit of `steps`, which keeps a teapot of 32 patches at a few million triangles.
+const MAX_PATCH_STEPS: usize = 256;
+
+/// Points around the axis of a `Lathe` without `segments`.
+const DEFAULT_LATHE_SEGMENTS: usize = 64;
+/// Points around the circle of a `Sweep` with a `radius` and without `segments`.
+const DEFAULT_SWEEP_SEGMENTS: usize = 32;
+/// Limit of `segments` for a `Lathe` or a `Sweep`.
+const MAX_SEGMENTS: usize = 1024;
+
+/// `value` as a number of `segments`, if it is a whole number from 3 to `MAX_SEGMENTS`.
+fn segment_count(value: Value) -> Option<usize> {
+    match value {
+        Value::Num(n) if n.fract() == 0.0 && (3.0..=MAX_SEGMENTS as f64).contains(&n) => {
+            Some(n as usize)
+        }
+        _ => None,
+    }
+}
+
+/// Time at which a moving object is at its starting position.
+pub(super) const MOTION_START: f64 = 0.0;
+/// Time at which a moving object has moved by its whole motion.
+pub(super) const MOTION_END: f64 = 1.0;
+
+/// `obj` moving by `motion` between `MOTION_START` and `MOTION_END`.
+fn moving(obj: HittableEnum, motion: Vec3) -> HittableEnum {
+    HittableEnum::MovingTranslation(Box::new(MovingTranslation::new(
+        obj,
+        Vec3::zero(),
+        motion,
+        MOTION_START,
+        MOTION_END,
+    )))
+}
+
+/// The objects `is_closed` accepts, for error messages.
+const CLOSED_OBJECTS: &str =
+    "Sphere, Box, Cylinder without open, Cone, Torus, Sweep, CSG objects or Objs of those";
+
+/// Whether `object` encloses a volume, which CSG needs to tell its inside from its outside.
+fn is_closed(object: &Object) -> bool {
+    match object {
+        Object::Sphere { .. }
+        | Object::Box { .. }
+        | Object::Cone { .. }
+        | Object::Torus { .. }
+        | Object::Sweep { .. }
+        | Object::Csg { .. } => true,
+        Object::Cylinder { open, .. } => !open,
+        Object::Objects { objects, .. } => objects.iter().all(is_closed),
+        _ => false,
+    }
 }
@@ -0,0 +1,200 @@
+use super::{mesh::Mesh, value::Value};
+use crate::tracer::vec3::{cross, unit_vector, Point3, Vec3};
+use std::fs;
+
+/// Highest degree of a patch in a file, which is far beyond what modelling tools write.
+const MAX_DEGREE: usize = 15;
+
+/// A Bézier patch whose control points are given row by row, with `u` along each row and `v`
+/// from one row to the next.
+pub(super) struct Patch {
+    u_degree: usize,
+    v_degree: usize,
+    points: Vec<Point3>,
+}
+
+impl Patch {
+    /// The bicubic patch of `control`, an array of 16 `Vec3` or of 4 rows of 4.
+    pub(super) fn from_control(control: Value) -> Option<Self> {
+        let Value::Array(members) = control else {
+            return None;
+        };
+        let members: Vec<Value> = if members.iter().all(|row| matches!(row, Value::Array(_))) {
+            members
+                .iter()
+                .map(|row| match row {
+                    Value::Array(row) if row.len() == 4 => Some(row.to_vec()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .flatten()
+                .collect()
+        } else {
+            members.to_vec()
+        };
+        let points = members
+            .into_iter()
+            .map(|member| match member {
+                Value::Vec3(x, y, z) => Some(Vec3::new(x, y, z)),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        (points.len() == 16).then_some(Patch {
+            u_degree: 3,
+            v_degree: 3,
+            points,
+        })
+    }
+
+    /// The point at `(u, v)` and the derivatives along `u` and along `v` there.
+    fn eval(&self, u: f64, v: f64) -> (Point3, Vec3, Vec3) {
+        let (bu, du) = bernstein(self.u_degree, u);
+        let (bv, dv) = bernstein(self.v_degree, v);
+        let (mut point, mut along_u, mut along_v) = (Vec3::zero(), Vec3::zero(), Vec3::zero());
+        for (j, (bv, dv)) in bv.iter().zip(dv.iter()).enumerate() {
+            for (i, (bu, du)) in bu.iter().zip(du.iter()).enumerate() {
+                let p = self.points[j * (self.u_degree + 1) + i];
+                point += p * (bu * bv);
+                along_u += p * (du * bv);
+                along_v += p * (bu * dv);
+            }
+        }
+        (point, along_u, along_v)
+    }
+
+    /// The normal at `(u, v)`. Where the patch is pinched to a point, like the top of a lid,
+    /// the derivatives vanish and the normal is taken a little towards the middle instead.
+    fn normal(&self, u: f64, v: f64) -> Vec3 {
+        let (_, along_u, along_v) = self.eval(u, v);
+        let normal = cross(&along_u, &along_v);
+        if normal.length() > 1e-12 {
+            return unit_vector(&normal);
+        }
+        let (_, along_u, along_v) = self.eval(u + (0.5 - u) * 1e-3, v + (0.5 - v) * 1e-3);
+        let normal = cross(&along_u, &along_v);
+        if normal.length() > 0.0 {
+            unit_vector(&normal)
+        } else {
+            Vec3::new(0.0, 1.0, 0.0)
+        }
+    }
+}
+
+/// The Bernstein polynomials of `degree` at `t` and their derivatives.
+fn bernstein(degree: usize, t: f64) -> (Vec<f64>, Vec<f64>) {
+    // raised one degree at a time, so the values of `degree - 1` give the derivatives
+    let mut values = vec![1.0];
+    let mut lower = vec![];
+    for n in 1..=degree {
+        lower = values.clone();
+        values = (0..=n)
+            .map(|i| {
+                let left = if i > 0 { t * lower[i - 1] } else { 0.0 };
+                let right = if i < n { (1.0 - t) * lower[i] } else { 0.0 };
+                left + right
+            })
+            .collect();
+    }
+    let derivatives = (0..=degree)
+        .map(|i| {
+            let left = if i > 0 { lower[i - 1] } else { 0.0 };
+            let right = if i < degree { lower[i] } else { 0.0 };
+            degree as f64 * (left - right)
+        })
+        .collect();
+    (values, derivatives)
+}
+
+/// The patches of a BPT file: the number of patches, then for each its degrees along `u` and
+/// `v` followed by its control points, one `x y z` per line. Lines starting with `#` are
+/// comments.
+pub(super) fn load(path: &str) -> Result<Vec<Patch>, String> {
+    let source =
+        fs::read_to_string(path).map_err(|e| format!("Cannot read \"{}\": {}", path, e))?;
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .map(|(number, line)| (number + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+    let mut numbers = |count: usize| -> Result<Vec<f64>, String> {
+        let (number, line) = lines
+            .next()
+            .ok_or_else(|| format!("\"{}\" ends in the middle of a patch", path))?;
+        let values = line
+            .split_whitespace()
+            .map(|token| token.parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .filter(|values| values.len() == count)
+            .ok_or_else(|| format!("\"{}\": line {}: Expected {} numbers", path, number, count))?;
+        Ok(values)
+    };
+    let whole = |n: f64| (n >= 0.0 && n.fract() == 0.0).then_some(n as usize);
+
+    let count = whole(numbers(1)?[0])
+        .filter(|count| *count > 0)
+        .ok_or_else(|| format!("\"{}\" must start with the number of patches", path))?;
+    let mut patches = Vec::new();
+    for _ in 0..count {
+        let degrees = numbers(2)?;
+        let (Some(u_degree), Some(v_degree)) = (whole(degrees[0]), whole(degrees[1])) else {
+            return Err(format!("\"{}\": Patch degrees must be whole numbers", path));
+        };
+        if !(1..=MAX_DEGREE).contains(&u_degree) || !(1..=MAX_DEGREE).contains(&v_degree) {
+            return Err(format!(
+                "\"{}\": Patch degrees must be from 1 to {}",
+                path, MAX_DEGREE
+            ));
+        }
+        let points = (0..(u_degree + 1) * (v_degree + 1))
+            .map(|_| numbers(3).map(|p| Vec3::new(p[0], p[1], p[2])))
+            .collect::<Result<Vec<_>, _>>()?;
+        patches.push(Patch {
+            u_degree,
+            v_degree,
+            points,
+        });
+    }
+    Ok(patches)
+}
+
+/// Each patch split into `steps` × `steps` quads of two triangles, with the normals of the
+/// surface and texture coordinates from 0 to 1 along `u` and `v` of each patch.
+pub(super) fn mesh(patches: &[Patch], steps: usize) -> Mesh {
+    let side = steps + 1;
+    let mut vertices = Vec::with_capacity(patches.len() * side * side);
+    let mut normals = Vec::with_capacity(vertices.capacity());
+    let mut uvs = Vec::with_capacity(vertices.capacity());
+    let mut faces = Vec::with_capacity(patches.len() * 2 * steps * steps);
+    for patch in patches.iter() {
+        let first = vertices.len();
+        for row in 0..side {
+            for column in 0..side {
+                let (u, v) = (column as f64 / steps as f64, row as f64 / steps as f64);
+                vertices.push(patch.eval(u, v).0);
+                normals.push(patch.normal(u, v));
+                uvs.push((u, v));
+            }
+        }
+        let index = |column: usize, row: usize| first + row * side + column;
+        for row in 0..steps {
+            for column in 0..steps {
+                let a = index(column, row);
+                let b = index(column + 1, row);
+                let c = index(column, row + 1);
+                let d = index(column + 1, row + 1);
+                // in this order the faces point along the cross product of u and v
+                faces.push([a, b, c]);
+                faces.push([b, d, c]);
+            }
+        }
+    }
+    Mesh {
+        vertices,
+        faces,
+        normals: Some(normals),
+        uvs: Some(uvs),
+        colors: None,
+    }
+}
@@ -0,0 +1,158 @@
+use super::value::Value;
+
+/// How `spline` moves between keyframes.
+enum Mode {
+    Linear,
+    /// Passes through every keyframe with a continuous tangent.
+    CatmullRom,
+    /// Passes through the first and last keyframes; the others are control points.
+    Bezier,
+}
+
+/// A keyframe value: a number is stored in the first component.
+#[derive(Clone, Copy, PartialEq)]
+enum Shape {
+    Num,
+    Vec3,
+}
+
+struct Keyframe {
+    time: f64,
+    value: [f64; 3],
+}
+
+/// `spline([mode,] t, [t0, v0], [t1, v1], ...)`: the value at time `t` of the curve through
+/// the keyframes. `mode` is `"linear"` (default), `"catmull_rom"` or `"bezier"`, and the
+/// values are all numbers or all `Vec3`. Before the first and after the last keyframe, the
+/// value is held.
+pub(super) fn spline(args: &[Value]) -> Result<Value, String> {
+    let (mode, args) = match args.first() {
+        Some(Value::Str(mode)) => (parse_mode(mode)?, &args[1..]),
+        _ => (Mode::Linear, args),
+    };
+    let (t, keyframes) = match args.split_first() {
+        Some((Value::Num(t), keyframes)) => (*t, keyframes),
+        _ => return Err("\"spline\" needs a number as the time".to_string()),
+    };
+    if keyframes.is_empty() {
+        return Err("\"spline\" needs at least one keyframe".to_string());
+    }
+    let mut shape = None;
+    let mut keys = Vec::with_capacity(keyframes.len());
+    for keyframe in keyframes.iter() {
+        let (key, key_shape) = parse_keyframe(keyframe)?;
+        if *shape.get_or_insert(key_shape) != key_shape {
+            return Err("\"spline\" keyframe values must all be numbers or all Vec3".to_string());
+        }
+        if keys
+            .last()
+            .is_some_and(|prev: &Keyframe| key.time <= prev.time)
+        {
+            return Err("\"spline\" keyframe times must be increasing".to_string());
+        }
+        keys.push(key);
+    }
+    let [x, y, z] = interpolate(&mode, &keys, t);
+    Ok(match shape {
+        Some(Shape::Num) => Value::Num(x),
+        _ => Value::Vec3(x, y, z),
+    })
+}
+
+fn parse_mode(mode: &str) -> Result<Mode, String> {
+    match mode {
+        "linear" => Ok(Mode::Linear),
+        "catmull_rom" => Ok(Mode::CatmullRom),
+        "bezier" => Ok(Mode::Bezier),
+        _ => Err(format!("\"spline\" has unknown mode \"{}\"", mode)),
+    }
+}
+
+fn parse_keyframe(keyframe: &Value) -> Result<(Keyframe, Shape), String> {
+    let Value::Array(members) = keyframe else {
+        return Err("\"spline\" keyframes must be [time, value]".to_string());
+    };
+    match &members[..] {
+        [Value::Num(time), Value::Num(n)] => Ok((
+            Keyframe {
+                time: *time,
+                value: [*n, 0.0, 0.0],
+            },
+            Shape::Num,
+        )),
+        [Value::Num(time), Value::Vec3(x, y, z)] => Ok((
+            Keyframe {
+                time: *time,
+                value: [*x, *y, *z],
+            },
+            Shape::Vec3,
+        )),
+        _ => Err("\"spline\" keyframes must be [time, value]".to_string()),
+    }
+}
+
+fn interpolate(mode: &Mode, keys: &[Keyframe], t: f64) -> [f64; 3] {
+    let last = keys.len() - 1;
+    // index of the keyframe starting the segment containing `t`, and the position within it
+    let (i, s) = if t <= keys[0].time {
+        (0, 0.0)
+    } else if t >= keys[last].time {
+        (last, 0.0)
+    } else {
+        let i = keys.iter().rposition(|key| key.time <= t).unwrap();
+        (i, (t - keys[i].time) / (keys[i + 1].time - keys[i].time))
+    };
+    if last == 0 || (i == last && !matches!(mode, Mode::Bezier)) {
+        return keys[i].value;
+    }
+    match mode {
+        Mode::Linear => lerp(keys[i].value, keys[i + 1].value, s),
+        Mode::CatmullRom => {
+            // cubic Hermite between the two keyframes, with the tangents in value per unit of
+            // time scaled to the length of the segment, so uneven keyframes keep their speed
+            let (p1, p2) = (keys[i].value, keys[i + 1].value);
+            let dt = keys[i + 1].time - keys[i].time;
+            let (m1, m2) = (tangent(keys, i), tangent(keys, i + 1));
+            let (s2, s3) = (s * s, s * s * s);
+            let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+            let h10 = s3 - 2.0 * s2 + s;
+            let h01 = -2.0 * s3 + 3.0 * s2;
+            let h11 = s3 - s2;
+            let mut value = [0.0; 3];
+            for (c, v) in value.iter_mut().enumerate() {
+                *v = h00 * p1[c] + h10 * dt * m1[c] + h01 * p2[c] + h11 * dt * m2[c];
+            }
+            value
+        }
+        Mode::Bezier => {
+            // keyframe times map evenly onto the curve parameter
+            let u = (i as f64 + s) / last as f64;
+            let mut points: Vec<[f64; 3]> = keys.iter().map(|key| key.value).collect();
+            while points.len() > 1 {
+                points = points.windows(2).map(|w| lerp(w[0], w[1], u)).collect();
+            }
+            points[0]
+        }
+    }
+}
+
+/// The Catmull-Rom tangent at keyframe `i`: the slope between its neighbours, or at either
+/// end half the slope to the only neighbour, as if the end keyframe were repeated.
+fn tangent(keys: &[Keyframe], i: usize) -> [f64; 3] {
+    let last = keys.len() - 1;
+    let (prev, next) = (i.saturating_sub(1), (i + 1).min(last));
+    let mut dt = keys[next].time - keys[prev].time;
+    if i == 0 || i == last {
+        dt *= 2.0;
+    }
+    let (a, b) = (keys[prev].value, keys[next].value);
+    [(b[0] - a[0]) / dt, (b[1] - a[1]) / dt, (b[2] - a[2]) / dt]
+}
+
+fn lerp(a: [f64; 3], b: [f64; 3], s: f64) -> [f64; 3] {
+    [
+        a[0] + (b[0] - a[0]) * s,
+        a[1] + (b[1] - a[1]) * s,
+        a[2] + (b[2] - a[2]) * s,
+    ]
+}
@@ -1,24 +1,38 @@
 use super::{
-    expression::eval_expr,
+    expression::{eval_expr, eval_exprs, next_arg},
     funcs::Functions,
-    object::eval_object,
-    value::{CameraConfigValue, ConfigValue, Value},
-    EvalError, Variables, COLOR_MAX,
+    limits::Budget,
+    object::{build_prototype, eval_object, MOTION_END, MOTION_START},
+    value::{CameraConfigValue, ConfigValue, FogValue, Value},
+    Cameras, EvalError, Variables, COLOR_MAX,
 };
-use crate::ast::Statement;
-use ray_tracer_rs::{
+use crate::ast::{camera::Projection, CameraConfig, Config, Expression, Span, Statement};
+use crate::tracer::{
     hittable::HittableEnum,
     vec3::{Color, Vec3},
 };
 
+/// How control leaves a statement. `Break` and `Continue` pass up through `if` bodies to the
+/// innermost `while`.
+pub(super) enum Flow {
+    Normal,
+    Break,
+    Continue,
+}
+
 pub(super) fn eval_stmt<'a>(
     ast: &'a Statement<'a>,
     variables: &mut Variables,
     funcs: &Functions<'a>,
     world: &mut Vec<HittableEnum>,
     config: &mut Option<ConfigValue>,
-    camera_config: &mut Option<CameraConfigValue>,
-) -> Result<(), EvalError<'a>> {
+    cameras: &mut Cameras,
+    budget: &mut Budget<'a>,
+) -> Result<Flow, EvalError<'a>> {
+    budget.statement().map_err(|message| EvalError {
+        span: budget.error_span(ast.span()),
+        message,
+    })?;
     match ast {
         Statement::Expression(expr) => {
             let _ = eval_expr(expr, variables, funcs)?;
@@ -27,6 +41,12 @@ pub(super) fn eval_stmt<'a>(
             let value = eval_expr(ex, variables, funcs)?;
             variables.insert(name.to_string(), value);
         }
+        Statement::VarDefault { name, ex, .. } => {
+            if !variables.contains_key(*name.fragment()) {
+                let value = eval_expr(ex, variables, funcs)?;
+                variables.insert(name.to_string(), value);
+            }
+        }
         Statement::If {
             cond,
             stmts,
@@ -41,160 +61,462 @@ pub(super) fn eval_stmt<'a>(
                     message: e,
                 });
             }
-            if cond_bool.unwrap() {
+            let stmts = if cond_bool.unwrap() {
+                Some(stmts)
+            } else {
+                else_stmts.as_ref()
+            };
+            if let Some(stmts) = stmts {
                 for stmt in stmts.iter() {
-                    eval_stmt(stmt, variables, funcs, world, config, camera_config)?;
+                    match eval_stmt(stmt, variables, funcs, world, config, cameras, budget)? {
+                        Flow::Normal => {}
+                        flow => return Ok(flow),
+                    }
+                }
+            }
+        }
+        Statement::While { span, cond, stmts } => {
+            budget.enter_loop(*span);
+            let mut iterations = 0;
+            'outer: loop {
+                let cond_val = eval_expr(cond, variables, funcs)?;
+                let cond_bool = cond_val.to_bool();
+                if let Err(e) = cond_bool {
+                    return Err(EvalError {
+                        span: Some(cond.span),
+                        message: e,
+                    });
+                }
+                if !cond_bool.unwrap() {
+                    break;
                 }
-            } else if let Some(ref else_stmts) = else_stmts {
-                for stmt in else_stmts.iter() {
-                    eval_stmt(stmt, variables, funcs, world, config, camera_config)?;
+                iterations += 1;
+                budget
+                    .loop_iteration(iterations)
+                    .map_err(|message| EvalError {
+                        span: Some(*span),
+                        message,
+                    })?;
+                for stmt in stmts.iter() {
+                    match eval_stmt(stmt, variables, funcs, world, config, cameras, budget)? {
+                        Flow::Normal => {}
+                        Flow::Break => break 'outer,
+                        Flow::Continue => continue 'outer,
+                    }
                 }
             }
+            budget.exit_loop();
         }
-        Statement::While { cond, stmts, .. } => loop {
-            let cond_val = eval_expr(cond, variables, funcs)?;
-            let cond_bool = cond_val.to_bool();
-            if let Err(e) = cond_bool {
+        Statement::Break => return Ok(Flow::Break),
+        Statement::Continue => return Ok(Flow::Continue),
+        Statement::Object { span, object } => {
+            eval_object(object, variables, funcs, world, budget).map_err(|e| EvalError {
+                span: e.span.or(Some(*span)),
+                ..e
+            })?;
+        }
+        Statement::Prototype {
+            span,
+            name,
+            objects,
+        } => {
+            let mut children = Vec::new();
+            for object in objects.iter() {
+                eval_object(object, variables, funcs, &mut children, budget).map_err(|e| {
+                    EvalError {
+                        span: e.span.or(Some(*span)),
+                        ..e
+                    }
+                })?;
+            }
+            variables.insert(name.to_string(), build_prototype(children));
+        }
+        Statement::Config { config: c, .. } => {
+            let args = eval_exprs(config_exprs(c), variables, funcs)?;
+            *config = Some(build_config(c, args)?);
+        }
+        Statement::Camera {
+            name, config: c, ..
+        } => {
+            let args = eval_exprs(camera_exprs(c), variables, funcs)?;
+            set_camera(cameras, *name, build_camera(c, args)?)?;
+        }
+    };
+    Ok(Flow::Normal)
+}
+
+/// Expressions a `Config` statement reads, in the order `build_config` consumes their values.
+/// Omitted optional properties are skipped.
+pub(super) fn config_exprs<'a>(c: &'a Config<'a>) -> Vec<&'a Expression<'a>> {
+    let mut exprs = vec![&c.width, &c.height, &c.samples_per_pixel];
+    exprs.extend(c.max_depth.as_ref());
+    exprs.extend(c.background.as_ref());
+    if let Some(fog) = c.fog.as_ref() {
+        exprs.extend([&fog.color, &fog.density]);
+    }
+    exprs
+}
+
+pub(super) fn build_config<'a>(
+    c: &'a Config<'a>,
+    args: Vec<Value>,
+) -> Result<ConfigValue, EvalError<'a>> {
+    let mut args = args.into_iter();
+    let width = match next_arg(&mut args) {
+        Value::Num(n) => n,
+        _ => {
+            return Err(EvalError {
+                span: Some(c.width.span),
+                message: "Invalid width".to_string(),
+            })
+        }
+    };
+    let height = match next_arg(&mut args) {
+        Value::Num(n) => n,
+        _ => {
+            return Err(EvalError {
+                span: Some(c.height.span),
+                message: "Invalid height".to_string(),
+            })
+        }
+    };
+    let samples_per_pixel = match next_arg(&mut args) {
+        Value::Num(n) => n,
+        _ => {
+            return Err(EvalError {
+                span: Some(c.samples_per_pixel.span),
+                message: "Invalid samples_per_pixel".to_string(),
+            })
+        }
+    };
+    let max_depth = match c.max_depth.as_ref() {
+        Some(expr) => match next_arg(&mut args) {
+            Value::Num(n) => n,
+            _ => {
                 return Err(EvalError {
-                    span: Some(cond.span),
-                    message: e,
-                });
+                    span: Some(expr.span),
+                    message: "Invalid max_depth".to_string(),
+                })
             }
-            if !cond_bool.unwrap() {
-                break;
+        },
+        None => 100.0,
+    };
+    let background = match c.background.as_ref() {
+        Some(expr) => match next_arg(&mut args) {
+            Value::Vec3(x, y, z) => Color::new(x, y, z) / COLOR_MAX,
+            _ => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Invalid sky_color".to_string(),
+                })
             }
-            for stmt in stmts.iter() {
-                match stmt {
-                    Statement::Break => break,
-                    Statement::Continue => continue,
-                    _ => eval_stmt(stmt, variables, funcs, world, config, camera_config)?,
-                }
+        },
+        None => Color::zero(),
+    };
+    let fog = match c.fog.as_ref() {
+        Some(fog) => match (next_arg(&mut args), next_arg(&mut args)) {
+            (Value::Vec3(r, g, b), Value::Num(density)) if density > 0.0 => Some(FogValue {
+                color: Color::new(r, g, b) / COLOR_MAX,
+                density,
+            }),
+            _ => {
+                return Err(EvalError {
+                    span: Some(fog.color.span),
+                    message: "Invalid fog; it needs a color and a positive density".to_string(),
+                })
             }
         },
-        Statement::Break | Statement::Continue => {}
-        Statement::Object { object, .. } => {
-            eval_object(object, variables, funcs, world)?;
+        None => None,
+    };
+    Ok(ConfigValue {
+        width,
+        height,
+        samples_per_pixel,
+        max_depth,
+        background,
+        fog,
+    })
+}
+
+/// Add a camera, replacing an earlier camera with the same name. Names become part of output
+/// file names, so they are limited to letters, digits, `_` and `-`.
+pub(super) fn set_camera<'a>(
+    cameras: &mut Cameras,
+    name: Option<Span<'a>>,
+    camera: CameraConfigValue,
+) -> Result<(), EvalError<'a>> {
+    if let Some(name) = name {
+        let valid = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+        if name.is_empty() || !name.chars().all(valid) {
+            return Err(EvalError {
+                span: Some(name),
+                message: "Camera names may only contain letters, digits, `_` and `-`".to_string(),
+            });
         }
-        Statement::Config { config: c, .. } => {
-            let width = match eval_expr(&c.width, variables, funcs)? {
-                Value::Num(n) => n,
-                _ => {
-                    return Err(EvalError {
-                        span: Some(c.width.span),
-                        message: "Invalid width".to_string(),
-                    })
-                }
-            };
-            let height = match eval_expr(&c.height, variables, funcs)? {
-                Value::Num(n) => n,
-                _ => {
+    }
+    let name = name.map(|name| name.to_string());
+    match cameras.iter_mut().find(|(n, _)| *n == name) {
+        Some((_, c)) => *c = camera,
+        None => cameras.push((name, camera)),
+    }
+    Ok(())
+}
+
+/// Expressions a `Camera` statement reads, in the order `build_camera` consumes their values.
+/// Omitted optional properties are skipped.
+pub(super) fn camera_exprs<'a>(c: &'a CameraConfig<'a>) -> Vec<&'a Expression<'a>> {
+    let mut exprs: Vec<&Expression> = c.lookfrom.iter().chain(c.lookat.iter()).collect();
+    exprs.extend(c.direction.as_ref());
+    exprs.extend(c.up.as_ref());
+    exprs.push(&c.angle);
+    exprs.extend(c.dist_to_focus.as_ref());
+    exprs.extend(c.focus_on.as_ref());
+    exprs.extend(c.aperture.as_ref());
+    exprs.extend(c.f_stop.as_ref());
+    exprs.extend(c.shutter_open.as_ref());
+    exprs.extend(c.shutter_close.as_ref());
+    exprs.extend(c.ortho_width.as_ref());
+    exprs
+}
+
+pub(super) fn build_camera<'a>(
+    c: &'a CameraConfig<'a>,
+    args: Vec<Value>,
+) -> Result<CameraConfigValue, EvalError<'a>> {
+    let mut args = args.into_iter();
+    if c.auto_frame {
+        if let Some(expr) = c.lookfrom.as_ref().or(c.lookat.as_ref()) {
+            return Err(EvalError {
+                span: Some(expr.span),
+                message: "lookfrom and lookat are computed by auto_frame".to_string(),
+            });
+        }
+    }
+    // without auto_frame, the parser requires both
+    let lookfrom = match c.lookfrom.as_ref() {
+        Some(expr) => match next_arg(&mut args) {
+            Value::Vec3(x, y, z) => Vec3::new(x, y, z),
+            _ => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Invalid look_from".to_string(),
+                })
+            }
+        },
+        None => Vec3::new(0.0, 0.0, 0.0),
+    };
+    let lookat = match c.lookat.as_ref() {
+        Some(expr) => match next_arg(&mut args) {
+            Value::Vec3(x, y, z) => Vec3::new(x, y, z),
+            _ => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Invalid look_at".to_string(),
+                })
+            }
+        },
+        None => Vec3::new(0.0, 0.0, 0.0),
+    };
+    let direction = match c.direction.as_ref() {
+        Some(expr) => match next_arg(&mut args) {
+            Value::Vec3(x, y, z) if (x, y, z) != (0.0, 0.0, 0.0) => Vec3::new(x, y, z),
+            _ => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Invalid direction".to_string(),
+                })
+            }
+        },
+        None => Vec3::new(0.0, 0.0, 1.0),
+    };
+    let up = match c.up.as_ref() {
+        Some(expr) => match next_arg(&mut args) {
+            Value::Vec3(x, y, z) => Vec3::new(x, y, z),
+            _ => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Invalid up".to_string(),
+                })
+            }
+        },
+        None => Vec3::new(0.0, 1.0, 0.0),
+    };
+    let angle = match next_arg(&mut args) {
+        Value::Num(n) => n,
+        _ => {
+            return Err(EvalError {
+                span: Some(c.angle.span),
+                message: "Invalid angle".to_string(),
+            })
+        }
+    };
+    let dist_to_focus = match c.dist_to_focus.as_ref() {
+        Some(expr) => match next_arg(&mut args) {
+            Value::Num(n) if n > 0.0 => Some(n),
+            Value::Num(_) => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "dist_to_focus must be positive".to_string(),
+                })
+            }
+            _ => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Invalid dist_to_focus".to_string(),
+                })
+            }
+        },
+        None => None,
+    };
+    let focus_on = match c.focus_on.as_ref() {
+        Some(expr) => {
+            if c.dist_to_focus.is_some() {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Specify either dist_to_focus or focus_on".to_string(),
+                });
+            }
+            match next_arg(&mut args) {
+                // with auto_frame, lookfrom is only known once the scene is built
+                Value::Vec3(x, y, z) if !c.auto_frame && Vec3::new(x, y, z) == lookfrom => {
                     return Err(EvalError {
-                        span: Some(c.height.span),
-                        message: "Invalid height".to_string(),
+                        span: Some(expr.span),
+                        message: "focus_on must differ from lookfrom".to_string(),
                     })
                 }
-            };
-            let samples_per_pixel = match eval_expr(&c.samples_per_pixel, variables, funcs)? {
-                Value::Num(n) => n,
+                Value::Vec3(x, y, z) => Some(Vec3::new(x, y, z)),
                 _ => {
                     return Err(EvalError {
-                        span: Some(c.samples_per_pixel.span),
-                        message: "Invalid samples_per_pixel".to_string(),
+                        span: Some(expr.span),
+                        message: "Invalid focus_on".to_string(),
                     })
                 }
-            };
-            let max_depth = match c.max_depth.as_ref() {
-                Some(expr) => match eval_expr(expr, variables, funcs)? {
-                    Value::Num(n) => n,
-                    _ => {
-                        return Err(EvalError {
-                            span: Some(c.max_depth.as_ref().unwrap().span),
-                            message: "Invalid max_depth".to_string(),
-                        })
-                    }
-                },
-                None => 100.0,
-            };
-            let background = match c.background.as_ref() {
-                Some(expr) => match eval_expr(expr, variables, funcs)? {
-                    Value::Vec3(x, y, z) => Color::new(x, y, z) / COLOR_MAX,
-                    _ => {
-                        return Err(EvalError {
-                            span: Some(c.background.as_ref().unwrap().span),
-                            message: "Invalid sky_color".to_string(),
-                        })
-                    }
-                },
-                None => Color::zero(),
-            };
-            *config = Some(ConfigValue {
-                width,
-                height,
-                samples_per_pixel,
-                max_depth,
-                background,
-            });
+            }
         }
-        Statement::Camera { config: c, .. } => {
-            let lookfrom = match eval_expr(&c.lookfrom, variables, funcs)? {
-                Value::Vec3(x, y, z) => Vec3::new(x, y, z),
-                _ => {
-                    return Err(EvalError {
-                        span: Some(c.lookfrom.span),
-                        message: "Invalid look_from".to_string(),
-                    })
-                }
-            };
-            let lookat = match eval_expr(&c.lookat, variables, funcs)? {
-                Value::Vec3(x, y, z) => Vec3::new(x, y, z),
-                _ => {
+        None => None,
+    };
+    let aperture = match c.aperture.as_ref() {
+        Some(expr) => match next_arg(&mut args) {
+            Value::Num(n) if n >= 0.0 => n,
+            Value::Num(_) => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "aperture must be non-negative".to_string(),
+                })
+            }
+            _ => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Invalid aperture".to_string(),
+                })
+            }
+        },
+        None => 0.0,
+    };
+    let f_stop = match c.f_stop.as_ref() {
+        Some(expr) => {
+            if c.aperture.is_some() {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Specify either aperture or f_stop".to_string(),
+                });
+            }
+            match next_arg(&mut args) {
+                Value::Num(n) if n > 0.0 => Some(n),
+                Value::Num(_) => {
                     return Err(EvalError {
-                        span: Some(c.lookat.span),
-                        message: "Invalid look_at".to_string(),
+                        span: Some(expr.span),
+                        message: "f_stop must be positive".to_string(),
                     })
                 }
-            };
-            let up = match c.up.as_ref() {
-                Some(expr) => match eval_expr(expr, variables, funcs)? {
-                    Value::Vec3(x, y, z) => Vec3::new(x, y, z),
-                    _ => {
-                        return Err(EvalError {
-                            span: Some(c.up.as_ref().unwrap().span),
-                            message: "Invalid up".to_string(),
-                        })
-                    }
-                },
-                None => Vec3::new(0.0, 1.0, 0.0),
-            };
-            let angle = match eval_expr(&c.angle, variables, funcs)? {
-                Value::Num(n) => n,
                 _ => {
                     return Err(EvalError {
-                        span: Some(c.angle.span),
-                        message: "Invalid angle".to_string(),
+                        span: Some(expr.span),
+                        message: "Invalid f_stop".to_string(),
                     })
                 }
-            };
-            let dist_to_focus = match c.dist_to_focus.as_ref() {
-                Some(expr) => match eval_expr(expr, variables, funcs)? {
-                    Value::Num(n) => n,
-                    _ => {
-                        return Err(EvalError {
-                            span: Some(c.dist_to_focus.as_ref().unwrap().span),
-                            message: "Invalid dist_to_focus".to_string(),
-                        })
-                    }
-                },
-                None => 10.0,
-            };
-            *camera_config = Some(CameraConfigValue {
-                lookfrom,
-                lookat,
-                up,
-                angle,
-                dist_to_focus,
-            })
+            }
         }
+        None => None,
     };
-    Ok(())
+    let shutter_open = match c.shutter_open.as_ref() {
+        Some(expr) => match next_arg(&mut args) {
+            Value::Num(n) => n,
+            _ => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Invalid shutter_open".to_string(),
+                })
+            }
+        },
+        None => MOTION_START,
+    };
+    let shutter_close = match c.shutter_close.as_ref() {
+        Some(expr) => match next_arg(&mut args) {
+            Value::Num(n) => n,
+            _ => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Invalid shutter_close".to_string(),
+                })
+            }
+        },
+        None => MOTION_END,
+    };
+    // objects only move between MOTION_START and MOTION_END, which their bounds cover
+    if !(MOTION_START <= shutter_open
+        && shutter_open <= shutter_close
+        && shutter_close <= MOTION_END)
+    {
+        return Err(EvalError {
+            span: c
+                .shutter_open
+                .as_ref()
+                .or(c.shutter_close.as_ref())
+                .map(|e| e.span),
+            message: format!(
+                "Shutter must satisfy {} <= shutter_open <= shutter_close <= {}",
+                MOTION_START, MOTION_END
+            ),
+        });
+    }
+    let ortho_width = match c.ortho_width.as_ref() {
+        Some(expr) => match next_arg(&mut args) {
+            Value::Num(n) if n > 0.0 => Some(n),
+            Value::Num(_) => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "ortho_width must be positive".to_string(),
+                })
+            }
+            _ => {
+                return Err(EvalError {
+                    span: Some(expr.span),
+                    message: "Invalid ortho_width".to_string(),
+                })
+            }
+        },
+        None => None,
+    };
+    if c.projection == Projection::Fisheye && !(angle > 0.0 && angle <= 360.0) {
+        return Err(EvalError {
+            span: Some(c.angle.span),
+            message: "angle of a fisheye camera must be in (0, 360]".to_string(),
+        });
+    }
+    Ok(CameraConfigValue {
+        lookfrom,
+        lookat,
+        auto_frame: c.auto_frame.then_some(direction),
+        up,
+        angle,
+        dist_to_focus,
+        focus_on,
+        aperture,
+        f_stop,
+        shutter_open,
+        shutter_close,
+        projection: c.projection,
+        ortho_width,
+    })
 }
@@ -0,0 +1,228 @@
+use super::mesh::Mesh;
+use crate::tracer::vec3::{cross, dot, unit_vector, Point3, Vec3};
+use std::f64::consts::PI;
+
+/// Points a cubic curve has per span between control points.
+const CUBIC_STEPS: usize = 8;
+
+/// `points` joined by straight lines, or with `cubic` by a Catmull-Rom curve through them.
+fn curve(points: &[Vec3], cubic: bool) -> Vec<Vec3> {
+    if !cubic || points.len() < 3 {
+        return points.to_vec();
+    }
+    let last = points.len() - 1;
+    let mut samples = Vec::with_capacity(last * CUBIC_STEPS + 1);
+    for i in 0..last {
+        // the end points are repeated so the curve reaches them
+        let p0 = points[i.saturating_sub(1)];
+        let (p1, p2) = (points[i], points[i + 1]);
+        let p3 = points[(i + 2).min(last)];
+        for step in 0..CUBIC_STEPS {
+            let t = step as f64 / CUBIC_STEPS as f64;
+            let (t2, t3) = (t * t, t * t * t);
+            samples.push(
+                (p1 * 2.0
+                    + (p2 - p0) * t
+                    + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+                    + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+                    * 0.5,
+            );
+        }
+    }
+    samples.push(points[last]);
+    samples
+}
+
+/// Normals averaged over the faces around each vertex, weighted by area.
+fn smooth_normals(vertices: &[Point3], faces: &[[usize; 3]]) -> Vec<Vec3> {
+    let mut normals = vec![Vec3::zero(); vertices.len()];
+    for face in faces.iter() {
+        let [a, b, c] = face.map(|i| vertices[i]);
+        let normal = cross(&(b - a), &(c - a));
+        for &i in face.iter() {
+            normals[i] += normal;
+        }
+    }
+    normals
+        .into_iter()
+        .map(|normal| {
+            if normal.length() == 0.0 {
+                Vec3::new(0.0, 1.0, 0.0)
+            } else {
+                unit_vector(&normal)
+            }
+        })
+        .collect()
+}
+
+/// The profile of `(r, y)` points revolved around the y axis in `segments` steps. A cubic
+/// profile is shaded smoothly.
+pub(super) fn lathe(points: &[(f64, f64)], cubic: bool, segments: usize) -> Mesh {
+    let profile: Vec<Vec3> = points.iter().map(|&(r, y)| Vec3::new(r, y, 0.0)).collect();
+    let profile = curve(&profile, cubic);
+    let mut vertices = Vec::with_capacity(profile.len() * segments);
+    for point in profile.iter() {
+        for segment in 0..segments {
+            let (sin, cos) = (2.0 * PI * segment as f64 / segments as f64).sin_cos();
+            vertices.push(Vec3::new(point.x() * cos, point.y(), point.x() * sin));
+        }
+    }
+    let index = |ring: usize, segment: usize| ring * segments + segment % segments;
+    let mut faces = Vec::with_capacity(2 * (profile.len() - 1) * segments);
+    for ring in 0..profile.len() - 1 {
+        for segment in 0..segments {
+            let a = index(ring, segment);
+            let b = index(ring, segment + 1);
+            let c = index(ring + 1, segment);
+            let d = index(ring + 1, segment + 1);
+            // in this order the faces point away from the axis for a profile going up
+            faces.push([a, c, b]);
+            faces.push([b, c, d]);
+        }
+    }
+    let normals = cubic.then(|| smooth_normals(&vertices, &faces));
+    Mesh {
+        vertices,
+        faces,
+        normals,
+        uvs: None,
+        colors: None,
+    }
+}
+
+/// Points of a circle of `radius` for the profile of a tube.
+pub(super) fn circle(radius: f64, segments: usize) -> Vec<(f64, f64)> {
+    (0..segments)
+        .map(|segment| {
+            let (sin, cos) = (2.0 * PI * segment as f64 / segments as f64).sin_cos();
+            (radius * cos, radius * sin)
+        })
+        .collect()
+}
+
+/// The closed `profile` moved along `path`, staying perpendicular to it, with both ends
+/// capped. A cubic path is shaded smoothly along its length.
+pub(super) fn sweep(path: &[Point3], profile: &[(f64, f64)], cubic: bool) -> Result<Mesh, String> {
+    if path.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err("Sweep path points must differ from the ones next to them".to_string());
+    }
+    // counterclockwise, so that the faces point outward
+    let area: f64 = (0..profile.len())
+        .map(|i| {
+            let ((x0, y0), (x1, y1)) = (profile[i], profile[(i + 1) % profile.len()]);
+            x0 * y1 - x1 * y0
+        })
+        .sum();
+    if area == 0.0 {
+        return Err("Sweep profile must enclose an area".to_string());
+    }
+    let mut profile = profile.to_vec();
+    if area < 0.0 {
+        profile.reverse();
+    }
+    let centroid = profile
+        .iter()
+        .fold((0.0, 0.0), |(x, y), (px, py)| (x + px, y + py));
+    let centroid = (
+        centroid.0 / profile.len() as f64,
+        centroid.1 / profile.len() as f64,
+    );
+    // the caps are fans from the centroid, which only cover the profile when every edge is
+    // seen from it turning counterclockwise
+    let star_shaped = (0..profile.len()).all(|i| {
+        let ((x0, y0), (x1, y1)) = (profile[i], profile[(i + 1) % profile.len()]);
+        let ((x0, y0), (x1, y1)) = (
+            (x0 - centroid.0, y0 - centroid.1),
+            (x1 - centroid.0, y1 - centroid.1),
+        );
+        x0 * y1 - x1 * y0 > 0.0
+    });
+    if !star_shaped {
+        return Err(
+            "Sweep profile must be star-shaped around the average of its points".to_string(),
+        );
+    }
+
+    let path = curve(path, cubic);
+    let last = path.len() - 1;
+    let tangents: Vec<Vec3> = (0..path.len())
+        .map(|i| {
+            let central = path[(i + 1).min(last)] - path[i.saturating_sub(1)];
+            // where the path doubles back on itself the central difference vanishes, so the
+            // direction it arrives from is used instead
+            if central.length() > 1e-9 {
+                unit_vector(&central)
+            } else {
+                unit_vector(&(path[i] - path[i - 1]))
+            }
+        })
+        .collect();
+    // each frame is the one before turned with the path, so the profile does not twist
+    let mut normal = cross(&tangents[0], &Vec3::new(0.0, 1.0, 0.0));
+    if normal.length() < 1e-6 {
+        normal = cross(&tangents[0], &Vec3::new(1.0, 0.0, 0.0));
+    }
+    let mut frames = Vec::with_capacity(path.len());
+    for tangent in tangents.iter() {
+        normal = unit_vector(&(normal - *tangent * dot(&normal, tangent)));
+        frames.push((normal, cross(tangent, &normal)));
+    }
+    let place = |ring: usize, (x, y): (f64, f64)| {
+        let (normal, binormal) = frames[ring];
+        path[ring] + normal * x + binormal * y
+    };
+
+    let count = profile.len();
+    let mut vertices = Vec::with_capacity((path.len() + 2) * count + 2);
+    for ring in 0..path.len() {
+        for &point in profile.iter() {
+            vertices.push(place(ring, point));
+        }
+    }
+    let index = |ring: usize, point: usize| ring * count + point % count;
+    let mut faces = Vec::with_capacity(2 * last * count + 2 * count);
+    for ring in 0..last {
+        for point in 0..count {
+            let a = index(ring, point);
+            let b = index(ring, point + 1);
+            let c = index(ring + 1, point);
+            let d = index(ring + 1, point + 1);
+            faces.push([a, b, c]);
+            faces.push([b, d, c]);
+        }
+    }
+    let mut normals = cubic.then(|| smooth_normals(&vertices, &faces));
+
+    // the caps have vertices of their own, so they stay flat
+    for (ring, start) in [(0, true), (last, false)] {
+        let center = vertices.len();
+        vertices.push(place(ring, centroid));
+        for &point in profile.iter() {
+            vertices.push(place(ring, point));
+        }
+        for point in 0..count {
+            let a = center + 1 + point;
+            let b = center + 1 + (point + 1) % count;
+            faces.push(if start {
+                [center, b, a]
+            } else {
+                [center, a, b]
+            });
+        }
+        if let Some(normals) = normals.as_mut() {
+            let outward = if start {
+                -tangents[ring]
+            } else {
+                tangents[ring]
+            };
+            normals.extend(std::iter::repeat_n(outward, count + 1));
+        }
+    }
+    Ok(Mesh {
+        vertices,
+        faces,
+        normals,
+        uvs: None,
+        colors: None,
+    })
+}
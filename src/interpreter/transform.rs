@@ -0,0 +1,94 @@
+use crate::tracer::vec3::{unit_vector, Vec3};
+
+/// An affine transform: the 3 rows of a 4x4 matrix whose last row is `[0, 0, 0, 1]`. A point
+/// `p` maps to the matrix times `[p.x, p.y, p.z, 1]`.
+#[derive(Clone, Copy, PartialEq)]
+pub(super) struct Affine {
+    pub(super) rows: [[f64; 4]; 3],
+}
+
+impl Affine {
+    pub(super) fn identity() -> Self {
+        Affine {
+            rows: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+            ],
+        }
+    }
+
+    pub(super) fn translation(offset: Vec3) -> Self {
+        let mut affine = Self::identity();
+        for axis in 0..3 {
+            affine.rows[axis][3] = offset[axis];
+        }
+        affine
+    }
+
+    pub(super) fn scale(factor: Vec3) -> Self {
+        let mut affine = Self::identity();
+        for axis in 0..3 {
+            affine.rows[axis][axis] = factor[axis];
+        }
+        affine
+    }
+
+    /// Rotation by `degrees` about `axis` through the origin, counterclockwise when looking
+    /// from the tip of `axis` like `rotateX`, `rotateY` and `rotateZ`. `axis` must not be zero.
+    pub(super) fn rotation(axis: Vec3, degrees: f64) -> Self {
+        let a = unit_vector(&axis);
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let t = 1.0 - cos;
+        let (x, y, z) = (a.x(), a.y(), a.z());
+        Affine {
+            rows: [
+                [
+                    t * x * x + cos,
+                    t * x * y - sin * z,
+                    t * x * z + sin * y,
+                    0.0,
+                ],
+                [
+                    t * x * y + sin * z,
+                    t * y * y + cos,
+                    t * y * z - sin * x,
+                    0.0,
+                ],
+                [
+                    t * x * z - sin * y,
+                    t * y * z + sin * x,
+                    t * z * z + cos,
+                    0.0,
+                ],
+            ],
+        }
+    }
+
+    /// `self` applied with `pivot` as the origin, e.g. a rotation about `pivot`.
+    pub(super) fn about(self, pivot: Vec3) -> Self {
+        Self::translation(-pivot)
+            .then(&self)
+            .then(&Self::translation(pivot))
+    }
+
+    /// `self` followed by `next`.
+    pub(super) fn then(&self, next: &Affine) -> Self {
+        let mut rows = [[0.0; 4]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..3).map(|k| next.rows[i][k] * self.rows[k][j]).sum();
+            }
+            row[3] += next.rows[i][3];
+        }
+        Affine { rows }
+    }
+
+    /// Determinant of the linear part; a transform with 0 flattens objects and has no inverse.
+    pub(super) fn determinant(&self) -> f64 {
+        let m = &self.rows;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+}
@@ -1,14 +1,25 @@
-use ray_tracer_rs::{material::MaterialEnum, texture::TextureEnum, vec3::Vec3};
+use crate::ast::camera::Projection;
+use crate::tracer::{
+    hittable::HittableEnum,
+    material::MaterialEnum,
+    texture::TextureEnum,
+    vec3::{Color, Vec3},
+};
 use serde::{Deserialize, Serialize};
+use std::{rc::Rc, str::FromStr, sync::Arc};
 
 #[derive(Clone)]
 pub(super) enum Value {
     Num(f64),
-    Str(String),
+    /// Strings and arrays are shared, so that loading a variable holding one does not copy it.
+    Str(Rc<str>),
     Bool(bool),
     Vec3(f64, f64, f64),
+    Array(Rc<[Value]>),
     Material(MaterialEnum),
     Texture(TextureEnum),
+    /// Objects of a `Prototype`, shared by its instances.
+    Prototype(Arc<HittableEnum>),
 }
 
 fn get_type_str(value: &Value) -> String {
@@ -19,8 +30,10 @@ fn get_type_str(value: &Value) -> String {
         Value::Vec3(x, y, z) => {
             return format!("Vec3({}, {}, {})", x, y, z);
         }
+        Value::Array(_) => "Array",
         Value::Material(_) => "Material",
         Value::Texture(_) => "Texture",
+        Value::Prototype(_) => "Prototype",
     }
     .to_string()
 }
@@ -42,6 +55,52 @@ impl Value {
     }
 }
 
+/// Value of a variable defined before evaluation, e.g. with `-D NAME=value`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefineValue {
+    Num(f64),
+    Vec3(f64, f64, f64),
+    Str(String),
+}
+
+impl FromStr for DefineValue {
+    type Err = String;
+
+    /// Parse a number, a vector like `<1, 2, 3>`, or a string (surrounding quotes are optional).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(n) = s.parse::<f64>() {
+            return Ok(DefineValue::Num(n));
+        }
+        if let Some(inner) = s.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+            let members = inner
+                .split(',')
+                .map(|m| m.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| format!("Invalid member for Vec3: \"{}\"", s))?;
+            return match members[..] {
+                [x, y, z] => Ok(DefineValue::Vec3(x, y, z)),
+                _ => Err(format!("Vec3 needs 3 members: \"{}\"", s)),
+            };
+        }
+        let s = s
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .unwrap_or(s);
+        Ok(DefineValue::Str(s.to_string()))
+    }
+}
+
+impl From<&DefineValue> for Value {
+    fn from(value: &DefineValue) -> Self {
+        match value {
+            DefineValue::Num(n) => Value::Num(*n),
+            DefineValue::Vec3(x, y, z) => Value::Vec3(*x, *y, *z),
+            DefineValue::Str(s) => Value::Str(s.as_str().into()),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ConfigValue {
     pub width: f64,
@@ -49,12 +108,29 @@ pub struct ConfigValue {
     pub samples_per_pixel: f64,
     pub max_depth: f64,
     pub background: Vec3,
+    pub fog: Option<FogValue>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FogValue {
+    pub color: Color,
+    pub density: f64,
 }
 
 pub(crate) struct CameraConfigValue {
     pub(crate) lookfrom: Vec3,
     pub(crate) lookat: Vec3,
+    /// View direction when `lookfrom` and `lookat` are computed to frame the scene.
+    pub(crate) auto_frame: Option<Vec3>,
     pub(crate) up: Vec3,
     pub(crate) angle: f64,
-    pub(crate) dist_to_focus: f64,
+    pub(crate) dist_to_focus: Option<f64>,
+    pub(crate) focus_on: Option<Vec3>,
+    pub(crate) aperture: f64,
+    pub(crate) f_stop: Option<f64>,
+    pub(crate) shutter_open: f64,
+    pub(crate) shutter_close: f64,
+    pub(crate) projection: Projection,
+    /// Width of the view of an orthographic camera, if given.
+    pub(crate) ortho_width: Option<f64>,
 }
@@ -0,0 +1,181 @@
+use super::{
+    compiler::{Chunk, Op},
+    expression::{binary_op, build_material, build_texture, call_fn},
+    funcs::{FnDecl, Functions},
+    limits::Budget,
+    object::{build_object, build_prototype},
+    statement::{build_camera, build_config, set_camera},
+    value::{ConfigValue, Value},
+    Cameras, EvalError, Variables,
+};
+use crate::tracer::hittable::HittableEnum;
+
+struct Vm {
+    stack: Vec<Value>,
+    slots: Vec<Option<Value>>,
+    /// Objects being collected for the world and for each object whose children are open.
+    groups: Vec<Vec<HittableEnum>>,
+}
+
+/// Run a compiled program. `variables` are assigned before the first instruction.
+pub(super) fn run<'a>(
+    chunk: &Chunk<'a>,
+    funcs: &Functions,
+    variables: Variables,
+    world: &mut Vec<HittableEnum>,
+    config: &mut Option<ConfigValue>,
+    cameras: &mut Cameras,
+    budget: &mut Budget<'a>,
+) -> Result<(), EvalError<'a>> {
+    let functions: Vec<&FnDecl> = chunk.functions.iter().map(|name| &funcs[name]).collect();
+    let mut vm = Vm {
+        stack: Vec::new(),
+        slots: vec![None; chunk.slot_names.len()],
+        groups: vec![std::mem::take(world)],
+    };
+    for (name, value) in variables {
+        // variables the program never mentions cannot be observed
+        if let Some(slot) = chunk.slot_indices.get(&name) {
+            vm.slots[*slot] = Some(value);
+        }
+    }
+    let mut loop_iterations = vec![0; chunk.loop_count];
+
+    let mut pc = 0;
+    while pc < chunk.code.len() {
+        let span = chunk.spans[pc];
+        let error = |message: String| EvalError { span, message };
+        match &chunk.code[pc] {
+            Op::Const(index) => vm.stack.push(chunk.constants[*index].clone()),
+            Op::Load(slot) => match &vm.slots[*slot] {
+                Some(value) => vm.stack.push(value.clone()),
+                None => {
+                    return Err(error(format!(
+                        "variable \"{}\" not found",
+                        chunk.slot_names[*slot]
+                    )))
+                }
+            },
+            Op::Defined(slot) => vm.stack.push(Value::Bool(vm.slots[*slot].is_some())),
+            Op::Store(slot) => vm.slots[*slot] = Some(vm.pop()),
+            Op::Pop => {
+                vm.pop();
+            }
+            Op::Call(index, argc) => {
+                let args = vm.pop_n(*argc);
+                let value = call_fn(functions[*index], &args).map_err(error)?;
+                vm.stack.push(value);
+            }
+            Op::BinOp(op) => {
+                let rhs = vm.pop();
+                let lhs = vm.pop();
+                let value = binary_op(*op, lhs, rhs).map_err(error)?;
+                vm.stack.push(value);
+            }
+            Op::ToBool => {
+                let value = vm.pop().to_bool().map_err(error)?;
+                vm.stack.push(Value::Bool(value));
+            }
+            Op::And | Op::Or => {
+                let (rhs, lhs) = (vm.pop_bool(), vm.pop_bool());
+                let value = match &chunk.code[pc] {
+                    Op::And => lhs && rhs,
+                    _ => lhs || rhs,
+                };
+                vm.stack.push(Value::Bool(value));
+            }
+            Op::Not => {
+                let value = vm.pop_bool();
+                vm.stack.push(Value::Bool(!value));
+            }
+            Op::Vec3 => {
+                let (z, y, x) = (vm.pop(), vm.pop(), vm.pop());
+                match (x, y, z) {
+                    (Value::Num(x), Value::Num(y), Value::Num(z)) => {
+                        vm.stack.push(Value::Vec3(x, y, z))
+                    }
+                    _ => return Err(error("Invalid member for Vec3".to_string())),
+                }
+            }
+            Op::Array(argc) => {
+                let members = vm.pop_n(*argc);
+                vm.stack.push(Value::Array(members.into()));
+            }
+            Op::Material(mat, argc) => {
+                let args = vm.pop_n(*argc);
+                vm.stack.push(build_material(mat, args)?);
+            }
+            Op::Texture(tex, argc) => {
+                let args = vm.pop_n(*argc);
+                vm.stack.push(build_texture(tex, args)?);
+            }
+            Op::Jump(dest) => {
+                pc = *dest;
+                continue;
+            }
+            Op::JumpIfFalse(dest) => {
+                if !vm.pop().to_bool().map_err(error)? {
+                    pc = *dest;
+                    continue;
+                }
+            }
+            Op::Tick => budget.statement().map_err(error)?,
+            Op::LoopEnter(id) => loop_iterations[*id] = 0,
+            Op::LoopIteration(id) => {
+                loop_iterations[*id] += 1;
+                budget.loop_iteration(loop_iterations[*id]).map_err(error)?;
+            }
+            Op::BeginGroup => vm.groups.push(Vec::new()),
+            Op::Object(object, argc, statement) => {
+                budget.object().map_err(error)?;
+                let args = vm.pop_n(*argc);
+                let children = if !object.children().is_empty() {
+                    vm.groups.pop().unwrap()
+                } else {
+                    Vec::new()
+                };
+                // errors without a span of their own are reported at the statement
+                let obj = build_object(object, args, children, budget).map_err(|e| EvalError {
+                    span: e.span.or(*statement),
+                    ..e
+                })?;
+                vm.groups.last_mut().unwrap().push(obj);
+            }
+            Op::Prototype => {
+                let objects = vm.groups.pop().unwrap();
+                vm.stack.push(build_prototype(objects));
+            }
+            Op::Config(c, argc) => {
+                let args = vm.pop_n(*argc);
+                *config = Some(build_config(c, args)?);
+            }
+            Op::Camera(name, c, argc) => {
+                let args = vm.pop_n(*argc);
+                set_camera(cameras, *name, build_camera(c, args)?)?;
+            }
+            Op::Fail(message) => return Err(error(message.clone())),
+        }
+        pc += 1;
+    }
+    *world = vm.groups.pop().unwrap();
+    Ok(())
+}
+
+impl Vm {
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("value stack underflow")
+    }
+
+    fn pop_n(&mut self, n: usize) -> Vec<Value> {
+        let at = self.stack.len() - n;
+        self.stack.split_off(at)
+    }
+
+    /// Pop a value already converted by `Op::ToBool`.
+    fn pop_bool(&mut self) -> bool {
+        match self.pop() {
+            Value::Bool(b) => b,
+            _ => panic!("Expected a boolean produced by ToBool"),
+        }
+    }
+}
@@ -1,15 +1,37 @@
+pub mod animation;
 pub mod ast;
 pub mod interpreter;
 pub mod parser;
+pub mod tracer;
 
 // ref: https://github.com/msakuta/ruscal/blob/ed869ab38ba0608b75ec63040bcc06eb8a6fc5d7/src/lib.rs
 // use std::{collections::HashMap, sync::atomic::AtomicBool};
-use std::sync::atomic::AtomicBool;
+pub use animation::{frame_output_path, Animation};
+use interpreter::{DefineValue, EvalOptions};
+use std::{str::FromStr, sync::atomic::AtomicBool, time::Duration};
 
 pub struct Args {
     pub source: Option<String>,
     pub output: String,
     pub show_ast: bool,
+    pub bench_eval: bool,
+    pub eval_options: EvalOptions,
+    pub animation: Animation,
+    pub cameras: CameraSelection,
+}
+
+/// Cameras rendered from the evaluated scene.
+pub enum CameraSelection {
+    /// The unnamed camera, or the first one if all are named.
+    Default,
+    Named(String),
+    /// Every camera, each to its own output file.
+    All,
+}
+
+/// Output path of the camera called `name`, e.g. `out.png` -> `out_top.png`.
+pub fn camera_output_path(output: &str, name: &str) -> String {
+    animation::output_path_with_suffix(output, name)
 }
 
 impl Args {
@@ -18,6 +40,10 @@ impl Args {
             source: None,
             output: "".to_string(),
             show_ast: false,
+            bench_eval: false,
+            eval_options: EvalOptions::default(),
+            animation: Animation::default(),
+            cameras: CameraSelection::Default,
         }
     }
 }
@@ -34,6 +60,10 @@ pub fn parse_args() -> Option<Args> {
     let mut source = None;
     let mut output = None;
     let mut show_ast = false;
+    let mut bench_eval = false;
+    let mut eval_options = EvalOptions::default();
+    let mut animation = Animation::default();
+    let mut cameras = CameraSelection::Default;
     let mut show_help = false;
     let mut args_is_empty = true;
 
@@ -45,6 +75,49 @@ pub fn parse_args() -> Option<Args> {
             "-h" => show_help = true,
             "-o" => output = args.next(),
             "-a" => show_ast = true,
+            "-b" => bench_eval = true,
+            "--frames" => match parse_option(&arg, args.next())? {
+                0 => {
+                    println!("Invalid value for {}: at least 1 frame is needed", arg);
+                    return None;
+                }
+                frames => animation.frames = Some(frames),
+            },
+            "--clock-start" => animation.clock_start = parse_option(&arg, args.next())?,
+            "--clock-end" => animation.clock_end = parse_option(&arg, args.next())?,
+            "--frame-jobs" => animation.jobs = parse_option(&arg, args.next())?,
+            "--resume" => animation.resume = true,
+            "--camera" => match args.next() {
+                Some(name) => cameras = CameraSelection::Named(name),
+                None => {
+                    println!("Invalid value for --camera");
+                    return None;
+                }
+            },
+            "--all-cameras" => cameras = CameraSelection::All,
+            "--frame-delay" => animation.frame_delay = parse_option(&arg, args.next())?,
+            "--loop-count" => animation.loop_count = parse_option(&arg, args.next())?,
+            "--max-loop-iterations" => {
+                eval_options.limits.max_loop_iterations = Some(parse_option(&arg, args.next())?)
+            }
+            "--max-statements" => {
+                eval_options.limits.max_statements = Some(parse_option(&arg, args.next())?)
+            }
+            "--max-objects" => {
+                eval_options.limits.max_objects = Some(parse_option(&arg, args.next())?)
+            }
+            "-D" | "--define" => eval_options.defines.push(parse_define(args.next())?),
+            define if define.starts_with("-D") => eval_options
+                .defines
+                .push(parse_define(Some(define[2..].to_string()))?),
+            "--max-eval-time" => {
+                let secs: f64 = parse_option(&arg, args.next())?;
+                let Ok(max_time) = Duration::try_from_secs_f64(secs) else {
+                    println!("Invalid value for {}", arg);
+                    return None;
+                };
+                eval_options.limits.max_time = Some(max_time);
+            }
             _ => {
                 if source.is_none() {
                     source = Some(arg);
@@ -61,6 +134,22 @@ pub fn parse_args() -> Option<Args> {
     if show_help || args_is_empty {
         let options = r#"    -o       file  Specify output file
     -a       Show parsed AST
+    -b       Benchmark scene evaluation (tree-walker vs bytecode VM) without rendering
+    --camera       name   Render the camera declared as `Camera "name" { ... }`
+    --all-cameras         Render every camera to its own file (out_<name>.png, ...)
+    -D, --define   NAME=value  Define a variable (number, <x, y, z> or string) before evaluation
+    --frames       n      Render n frames to numbered files (out_0001.png, ...),
+                          or to a single animation when -o ends with .gif or .apng
+    --clock-start  x      Value of `clock` in the first frame (default: 0)
+    --clock-end    x      Value of `clock` in the last frame (default: 1)
+    --frame-jobs   n      Render n frames in parallel (default: 1)
+    --resume              Skip frames whose output file already exists
+    --frame-delay  ms     Display time of each frame of a .gif/.apng output (default: 100)
+    --loop-count   n      Times a .gif/.apng output is played, 0 loops forever (default: 0)
+    --max-loop-iterations  n  Abort when a single while loop runs more than n iterations
+    --max-statements       n  Abort after executing more than n statements
+    --max-objects          n  Abort after building more than n objects
+    --max-eval-time        s  Abort when evaluation takes longer than s seconds
         }"#;
         println!(
             r#"Usage: {exe} [options] [source.txt]
@@ -76,13 +165,52 @@ Options:
         return None;
     }
 
+    if animation.frames.is_some() && eval_options.defines.iter().any(|(name, _)| name == "clock") {
+        println!("`clock` is set for each frame and cannot be defined with --frames");
+        return None;
+    }
+
     Some(Args {
         source,
         output: output.unwrap_or_else(|| "temp.png".to_string()),
         show_ast,
+        bench_eval,
+        eval_options,
+        animation,
+        cameras,
     })
 }
 
+fn parse_define(define: Option<String>) -> Option<(String, DefineValue)> {
+    let Some((name, value)) = define.as_deref().and_then(|d| d.split_once('=')) else {
+        println!("Invalid define, expected NAME=value");
+        return None;
+    };
+    let name = name.trim();
+    if !parser::is_identifier(name) {
+        println!(
+            "Invalid define name {:?}: use letters, digits and _, not starting with a digit",
+            name
+        );
+        return None;
+    }
+    match value.parse::<DefineValue>() {
+        Ok(value) => Some((name.to_string(), value)),
+        Err(e) => {
+            println!("Invalid define for {}: {}", name, e);
+            None
+        }
+    }
+}
+
+fn parse_option<T: FromStr>(name: &str, value: Option<String>) -> Option<T> {
+    let parsed = value.as_deref().and_then(|v| v.parse().ok());
+    if parsed.is_none() {
+        println!("Invalid value for {}", name);
+    }
+    parsed
+}
+
 #[macro_export]
 macro_rules! dprintln {
     ($fmt:literal) => {
@@ -1,7 +1,21 @@
 use image::RgbImage;
 #[cfg(feature = "execution")]
-use kov_ray::interpreter::interpret;
+use kov_ray::interpreter::{eval_ast_with_options, render, DefineValue, EvalOptions, Scene};
 use kov_ray::parser;
+#[cfg(feature = "execution")]
+use kov_ray::{
+    animation::Container, ast::AST, camera_output_path, frame_output_path, Animation,
+    CameraSelection,
+};
+#[cfg(feature = "execution")]
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
 
 #[cfg(feature = "execution")]
 fn main() {
@@ -25,8 +39,234 @@ fn main() {
     if args.show_ast {
         println!("{:#?}", ast);
     }
-    let (image_buffer, width, height) = interpret(&ast);
-    let img = RgbImage::from_raw(width, height, image_buffer).expect("incorrect image buffer size");
+    let animation = args.animation;
+    if args.bench_eval {
+        bench_eval(&ast, &clock_options(&args.eval_options, animation.clock(0)));
+        return;
+    }
+    let cameras = args.cameras;
+    match (Container::from_path(&output), animation.frames) {
+        (Some(container), frames) => {
+            let frames = frames.unwrap_or(1);
+            let images = render_frames(
+                &ast,
+                &args.eval_options,
+                &animation,
+                frames,
+                &cameras,
+                &output,
+            );
+            for (path, images) in images {
+                container
+                    .save(&path, images, &animation)
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to save animation {}: {}", path, e);
+                        std::process::exit(1);
+                    });
+            }
+        }
+        (None, None) => {
+            let scene = evaluate(&ast, &clock_options(&args.eval_options, animation.clock(0)));
+            for (name, path) in selected_cameras(&scene, &cameras, &output) {
+                render_camera(&scene, name.as_deref())
+                    .save(path)
+                    .expect("failed to save image");
+            }
+        }
+        (None, Some(frames)) => render_animation(
+            &ast,
+            &args.eval_options,
+            &animation,
+            frames,
+            &cameras,
+            &output,
+        ),
+    }
+}
 
-    img.save(output).expect("failed to save image");
+/// Options for evaluating the scene at `clock`. In a single image, a `clock` given with `-D`
+/// takes precedence; with `--frames` defining it is rejected.
+#[cfg(feature = "execution")]
+fn clock_options(options: &EvalOptions, clock: f64) -> EvalOptions {
+    let mut options = options.clone();
+    options
+        .defines
+        .insert(0, ("clock".to_string(), DefineValue::Num(clock)));
+    options
+}
+
+#[cfg(feature = "execution")]
+fn evaluate(ast: &AST, options: &EvalOptions) -> Scene {
+    eval_ast_with_options(ast, options).unwrap_or_else(|e| panic!("Error: {:?}", e))
+}
+
+/// Cameras to render with the path of their image: `output` for the selected camera, or
+/// `out_<name>.png` for every named camera with `--all-cameras`, where the unnamed camera keeps
+/// `output` itself.
+#[cfg(feature = "execution")]
+fn selected_cameras(
+    scene: &Scene,
+    selection: &CameraSelection,
+    output: &str,
+) -> Vec<(Option<String>, String)> {
+    match selection {
+        CameraSelection::Default => vec![(None, output.to_string())],
+        CameraSelection::Named(name) => vec![(Some(name.clone()), output.to_string())],
+        CameraSelection::All => scene
+            .camera_names()
+            .into_iter()
+            .map(|name| {
+                let path = name.map_or_else(
+                    || output.to_string(),
+                    |name| camera_output_path(output, name),
+                );
+                (name.map(str::to_string), path)
+            })
+            .collect(),
+    }
+}
+
+#[cfg(feature = "execution")]
+fn render_camera(scene: &Scene, name: Option<&str>) -> RgbImage {
+    let camera = scene.camera(name).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    if let Some(name) = name {
+        println!("Rendering camera \"{}\"", name);
+    }
+    let (image_buffer, width, height) = render(scene, camera);
+    RgbImage::from_raw(width, height, image_buffer).expect("incorrect image buffer size")
+}
+
+/// Render every frame to numbered files.
+#[cfg(feature = "execution")]
+fn render_animation(
+    ast: &AST,
+    options: &EvalOptions,
+    animation: &Animation,
+    frames: usize,
+    cameras: &CameraSelection,
+    output: &str,
+) {
+    // with a single camera the frame's file is known before evaluating, so a resumed frame is
+    // not evaluated at all; `--all-cameras` needs the scene for the camera names
+    let done = |frame: usize| {
+        let path = frame_output_path(output, frame + 1);
+        let skip = animation.resume
+            && !matches!(cameras, CameraSelection::All)
+            && Path::new(&path).exists();
+        if skip {
+            println!("Skipping frame {}: {} already exists", frame + 1, path);
+        }
+        skip
+    };
+    for_each_frame(ast, options, animation, frames, done, |frame, scene| {
+        for (name, path) in selected_cameras(&scene, cameras, output) {
+            let path = frame_output_path(&path, frame + 1);
+            if animation.resume && Path::new(&path).exists() {
+                println!("Skipping frame {}: {} already exists", frame + 1, path);
+                continue;
+            }
+            render_camera(&scene, name.as_deref())
+                .save(&path)
+                .expect("failed to save image");
+        }
+    });
+}
+
+/// Render every frame in memory, and return the frames of each output path in frame order.
+#[cfg(feature = "execution")]
+fn render_frames(
+    ast: &AST,
+    options: &EvalOptions,
+    animation: &Animation,
+    frames: usize,
+    cameras: &CameraSelection,
+    output: &str,
+) -> Vec<(String, Vec<RgbImage>)> {
+    let rendered = Mutex::new(vec![Vec::new(); frames]);
+    for_each_frame(
+        ast,
+        options,
+        animation,
+        frames,
+        |_| false,
+        |frame, scene| {
+            let images: Vec<_> = selected_cameras(&scene, cameras, output)
+                .into_iter()
+                .map(|(name, path)| (path, render_camera(&scene, name.as_deref())))
+                .collect();
+            rendered.lock().unwrap()[frame] = images;
+        },
+    );
+    let mut animations: Vec<(String, Vec<RgbImage>)> = Vec::new();
+    for (path, image) in rendered.into_inner().unwrap().into_iter().flatten() {
+        match animations.iter_mut().find(|(p, _)| *p == path) {
+            Some((_, images)) => images.push(image),
+            None => animations.push((path, vec![image])),
+        }
+    }
+    animations
+}
+
+/// Call `f` with each 0-based frame and the scene evaluated for it, `animation.jobs` frames at
+/// a time. Frames for which `skip` returns true are not evaluated.
+#[cfg(feature = "execution")]
+fn for_each_frame<S, F>(
+    ast: &AST,
+    options: &EvalOptions,
+    animation: &Animation,
+    frames: usize,
+    skip: S,
+    f: F,
+) where
+    S: Fn(usize) -> bool + Sync,
+    F: Fn(usize, Scene) + Sync,
+{
+    let next_frame = AtomicUsize::new(0);
+    thread::scope(|s| {
+        for _ in 0..animation.jobs.max(1) {
+            s.spawn(|| loop {
+                let frame = next_frame.fetch_add(1, Ordering::Relaxed);
+                if frame >= frames {
+                    break;
+                }
+                if skip(frame) {
+                    continue;
+                }
+                let clock = animation.clock(frame);
+                println!(
+                    "Rendering frame {}/{} (clock = {})",
+                    frame + 1,
+                    frames,
+                    clock
+                );
+                f(frame, evaluate(ast, &clock_options(options, clock)));
+            });
+        }
+    });
+}
+
+#[cfg(feature = "execution")]
+fn bench_eval(ast: &AST, options: &EvalOptions) {
+    use kov_ray::interpreter::eval_ast_tree_walk;
+    use std::time::Instant;
+
+    let start = Instant::now();
+    if let Err(e) = eval_ast_tree_walk(ast, options) {
+        panic!("Error: {:?}", e);
+    }
+    let tree_walk = start.elapsed();
+    let start = Instant::now();
+    if let Err(e) = eval_ast_with_options(ast, options) {
+        panic!("Error: {:?}", e);
+    }
+    let vm = start.elapsed();
+    println!("tree-walker: {:?}", tree_walk);
+    println!("bytecode VM: {:?}", vm);
+    println!(
+        "speedup: {:.2}x",
+        tree_walk.as_secs_f64() / vm.as_secs_f64()
+    );
 }
@@ -2,7 +2,7 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{alpha1, alphanumeric1, char, multispace0},
-    combinator::recognize,
+    combinator::{all_consuming, recognize},
     error::ParseError,
     multi::many0,
     sequence::{delimited, pair},
@@ -43,11 +43,16 @@ fn identifier(input: Span) -> IResult<Span, Span> {
     ))(input)
 }
 
+/// Whether all of `name` is an identifier, e.g. for a variable defined on the command line.
+pub fn is_identifier(name: &str) -> bool {
+    all_consuming(identifier)(Span::new(name)).is_ok()
+}
+
 fn calc_offset<'a>(i: Span<'a>, r: Span<'a>) -> Span<'a> {
     i.take(i.offset(&r))
 }
 
-pub fn parse(i: &str) -> Result<AST, nom::error::Error<Span>> {
+pub fn parse(i: &str) -> Result<AST<'_>, nom::error::Error<Span<'_>>> {
     let i = Span::new(i);
     let res = statements_finish(i)?;
     Ok(res)
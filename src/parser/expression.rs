@@ -5,7 +5,7 @@ use nom::{
     bytes::complete::{tag, take_until},
     character::complete::{char, multispace0, none_of},
     combinator::{cut, opt},
-    multi::{fold_many0, many0},
+    multi::{fold_many0, many0, separated_list0},
     number::complete::recognize_float,
     sequence::{delimited, pair, preceded, terminated},
     IResult,
@@ -16,6 +16,8 @@ fn factor(i: Span) -> IResult<Span, Expression> {
         str_literal,
         num_literal,
         vec3_expr,
+        array_expr,
+        defined_expr,
         func_call,
         ident,
         not_factor,
@@ -39,6 +41,18 @@ fn func_call(i: Span) -> IResult<Span, Expression> {
     ))
 }
 
+fn defined_expr(i: Span) -> IResult<Span, Expression> {
+    let (r, _) = space_delimited(tag("defined"))(i)?;
+    let (r, name) = space_delimited(delimited(tag("("), space_delimited(identifier), tag(")")))(r)?;
+    Ok((
+        r,
+        Expression {
+            expr: ExprEnum::Defined(name),
+            span: i,
+        },
+    ))
+}
+
 fn term(input: Span) -> IResult<Span, Expression> {
     let (r, init) = factor(input)?;
 
@@ -181,8 +195,15 @@ pub(super) fn vec3_expr(i0: Span) -> IResult<Span, Expression> {
     ))
 }
 
-pub(super) fn vec3_ident_expr(i: Span) -> IResult<Span, Expression> {
-    alt((vec3_expr, ident))(i)
+fn array_expr(i0: Span) -> IResult<Span, Expression> {
+    let (i, _) = space_delimited(tag("["))(i0)?;
+    let (i, members) = separated_list0(space_delimited(tag(",")), space_delimited(expr))(i)?;
+    let (i, _) = opt(space_delimited(tag(",")))(i)?;
+    let (i, _) = space_delimited(tag("]"))(i)?;
+    Ok((
+        i,
+        Expression::new(ExprEnum::Array(members), calc_offset(i0, i)),
+    ))
 }
 
 pub fn comment_expr(i: Span) -> IResult<Span, Expression> {
@@ -1,26 +1,29 @@
 use super::{
     close_brace,
-    expression::{comment_expr, expr, vec3_ident_expr},
+    expression::{comment_expr, expr},
     material::material_expr,
     open_brace, space_delimited,
 };
 use crate::ast::{
-    object::{AffineProperties, Rotate, RotateAxis},
+    object::{
+        AffineProperties, BlobComponent, CsgOperation, HeightSource, Interpolation, PatchSource,
+        QuadShape, Rotate, RotateAxis, SweepProfile,
+    },
     Expression, Object, Span,
 };
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    combinator::opt,
+    combinator::{opt, value},
     multi::{many0, many1},
-    sequence::delimited,
+    sequence::{delimited, pair, terminated},
     IResult,
 };
 
 fn translate_decl(i: Span) -> IResult<Span, AffineProperties> {
     let (i, expr) = delimited(
         space_delimited(tag("translate:")),
-        space_delimited(vec3_ident_expr),
+        space_delimited(expr),
         space_delimited(tag(",")),
     )(i)?;
     let (i, _) = opt(space_delimited(comment_expr))(i)?;
@@ -72,12 +75,75 @@ fn rotate_z_decl(i: Span) -> IResult<Span, AffineProperties> {
     ))
 }
 
+fn rotate_decl(i: Span) -> IResult<Span, AffineProperties> {
+    let (i, _) = space_delimited(tag("rotate:"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+    let (i, axis) = delimited(
+        space_delimited(tag("axis:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, angle) = delimited(
+        space_delimited(tag("angle:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+    let (i, _) = space_delimited(tag(","))(i)?;
+    Ok((i, AffineProperties::AxisRotate { axis, angle }))
+}
+
+fn scale_decl(i: Span) -> IResult<Span, AffineProperties> {
+    let (i, expr) = delimited(
+        space_delimited(tag("scale:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, AffineProperties::Scale(expr)))
+}
+
+fn matrix_decl(i: Span) -> IResult<Span, AffineProperties> {
+    let (i, expr) = delimited(
+        space_delimited(tag("matrix:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, AffineProperties::Matrix(expr)))
+}
+
+fn pivot_decl(i: Span) -> IResult<Span, AffineProperties> {
+    let (i, expr) = delimited(
+        space_delimited(tag("pivot:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, AffineProperties::Pivot(expr)))
+}
+
+fn motion_decl(i: Span) -> IResult<Span, AffineProperties> {
+    let (i, expr) = delimited(
+        space_delimited(tag("motion:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, AffineProperties::Motion(expr)))
+}
+
 fn affine_properties(i: Span) -> IResult<Span, Vec<AffineProperties>> {
     let (i, res) = space_delimited(many0(alt((
         translate_decl,
         rotate_x_decl,
         rotate_y_decl,
         rotate_z_decl,
+        rotate_decl,
+        scale_decl,
+        matrix_decl,
+        pivot_decl,
+        motion_decl,
     ))))(i)?;
 
     Ok((i, res))
@@ -97,17 +163,47 @@ fn objects(i: Span) -> IResult<Span, Object> {
     Ok((i_start, Object::Objects { objects, affine }))
 }
 
-fn sphere_center_decl(i: Span) -> IResult<Span, (&str, Expression)> {
+fn csg_object(i: Span) -> IResult<Span, Object> {
+    let (i, operation) = space_delimited(alt((
+        value(CsgOperation::Difference, tag("Difference")),
+        value(CsgOperation::Intersection, tag("Intersection")),
+        value(CsgOperation::Merge, tag("Merge")),
+    )))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+    let (i, objects) = many1(object)(i)?;
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+    Ok((
+        i,
+        Object::Csg {
+            operation,
+            objects,
+            affine,
+        },
+    ))
+}
+
+fn sphere_center_decl(i: Span<'_>) -> IResult<Span<'_>, (&str, Expression<'_>)> {
     let (i, expr) = delimited(
         space_delimited(tag("center:")),
-        space_delimited(vec3_ident_expr),
+        space_delimited(expr),
         space_delimited(tag(",")),
     )(i)?;
     let (i, _) = opt(space_delimited(comment_expr))(i)?;
     Ok((i, ("center", expr)))
 }
 
-fn sphere_radius_decl(i: Span) -> IResult<Span, (&str, Expression)> {
+fn sphere_center_end_decl(i: Span<'_>) -> IResult<Span<'_>, (&str, Expression<'_>)> {
+    let (i, expr) = delimited(
+        space_delimited(tag("center_end:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ("center_end", expr)))
+}
+
+fn sphere_radius_decl(i: Span<'_>) -> IResult<Span<'_>, (&str, Expression<'_>)> {
     let (i, expr) = delimited(
         space_delimited(tag("radius:")),
         space_delimited(expr),
@@ -117,7 +213,7 @@ fn sphere_radius_decl(i: Span) -> IResult<Span, (&str, Expression)> {
     Ok((i, ("radius", expr)))
 }
 
-fn material_decl(i: Span) -> IResult<Span, (&str, Expression)> {
+fn material_decl(i: Span<'_>) -> IResult<Span<'_>, (&str, Expression<'_>)> {
     let (i, expr) = delimited(
         space_delimited(tag("material:")),
         space_delimited(material_expr),
@@ -134,14 +230,21 @@ fn sphere_object(i: Span) -> IResult<Span, Object> {
     let mut center: Option<Expression> = None;
     let mut radius: Option<Expression> = None;
     let mut material: Option<Expression> = None;
+    let mut center_end: Option<Expression> = None;
     let mut affine: Vec<AffineProperties> = Vec::new();
 
-    let (i, options) = many0(alt((sphere_center_decl, sphere_radius_decl, material_decl)))(i)?;
+    let (i, options) = many0(alt((
+        sphere_center_decl,
+        sphere_center_end_decl,
+        sphere_radius_decl,
+        material_decl,
+    )))(i)?;
 
     options.iter().for_each(|(key, value)| match *key {
         "center" => center = Some(value.clone()),
         "radius" => radius = Some(value.clone()),
         "material" => material = Some(value.clone()),
+        "center_end" => center_end = Some(value.clone()),
         _ => {}
     });
 
@@ -162,6 +265,7 @@ fn sphere_object(i: Span) -> IResult<Span, Object> {
             center: center.unwrap(),
             radius: radius.unwrap(),
             material: material.unwrap(),
+            center_end,
             affine,
         },
     ))
@@ -184,9 +288,9 @@ fn vertex_decl(i: Span) -> IResult<Span, SquareObjectPropertiesEnum> {
         space_delimited(tag("vertex:")),
         space_delimited(|i| {
             let (i, _) = tag("(")(i)?;
-            let (i, v1) = vec3_ident_expr(i)?;
+            let (i, v1) = expr(i)?;
             let (i, _) = tag(",")(i)?;
-            let (i, v2) = vec3_ident_expr(i)?;
+            let (i, v2) = expr(i)?;
             let (i, _) = tag(")")(i)?;
             Ok((i, (v1, v2)))
         }),
@@ -239,6 +343,113 @@ fn general_square_object_properties(i: Span) -> IResult<Span, SquareObjectProper
     ))
 }
 
+#[derive(Debug)]
+enum BlobPropertiesEnum<'src> {
+    Threshold(Expression<'src>),
+    Components(Vec<BlobComponent<'src>>),
+    Material(Expression<'src>),
+}
+
+fn blob_threshold_decl(i: Span) -> IResult<Span, BlobPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("threshold:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, BlobPropertiesEnum::Threshold(expr)))
+}
+
+fn blob_component(i: Span) -> IResult<Span, BlobComponent> {
+    let (i, _) = space_delimited(open_brace)(i)?;
+    let (i, center) = delimited(
+        space_delimited(tag("center:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, radius) = delimited(
+        space_delimited(tag("radius:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, strength) = delimited(
+        space_delimited(tag("strength:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+    Ok((
+        i,
+        BlobComponent {
+            center,
+            radius,
+            strength,
+        },
+    ))
+}
+
+fn blob_components_decl(i: Span) -> IResult<Span, BlobPropertiesEnum> {
+    let (i, components) = delimited(
+        pair(
+            space_delimited(tag("components:")),
+            space_delimited(tag("[")),
+        ),
+        many1(terminated(blob_component, space_delimited(opt(tag(","))))),
+        pair(space_delimited(tag("]")), space_delimited(tag(","))),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, BlobPropertiesEnum::Components(components)))
+}
+
+fn blob_material_decl(i: Span) -> IResult<Span, BlobPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, BlobPropertiesEnum::Material(expr)))
+}
+
+fn blob_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Blob"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut threshold: Option<Expression> = None;
+    let mut components: Option<Vec<BlobComponent>> = None;
+    let mut material: Option<Expression> = None;
+
+    let (i, options) = many0(alt((
+        blob_threshold_decl,
+        blob_components_decl,
+        blob_material_decl,
+    )))(i)?;
+    options.into_iter().for_each(|option| match option {
+        BlobPropertiesEnum::Threshold(expr) => threshold = Some(expr),
+        BlobPropertiesEnum::Components(c) => components = Some(c),
+        BlobPropertiesEnum::Material(expr) => material = Some(expr),
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    let (Some(threshold), Some(components), Some(material)) = (threshold, components, material)
+    else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::Blob {
+            threshold,
+            components,
+            material,
+            affine,
+        },
+    ))
+}
+
 fn box_object(i: Span) -> IResult<Span, Object> {
     let (i, _) = space_delimited(tag("Box"))(i)?;
     let (i, properties) = general_square_object_properties(i)?;
@@ -265,6 +476,1363 @@ fn plane_object(i: Span) -> IResult<Span, Object> {
     ))
 }
 
+#[derive(Debug)]
+enum QuadPropertiesEnum<'src> {
+    Origin(Expression<'src>),
+    U(Expression<'src>),
+    V(Expression<'src>),
+    Shape(QuadShape),
+    Material(Expression<'src>),
+}
+
+fn quad_origin_decl(i: Span) -> IResult<Span, QuadPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("origin:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, QuadPropertiesEnum::Origin(expr)))
+}
+
+fn quad_u_decl(i: Span) -> IResult<Span, QuadPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("u:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, QuadPropertiesEnum::U(expr)))
+}
+
+fn quad_v_decl(i: Span) -> IResult<Span, QuadPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("v:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, QuadPropertiesEnum::V(expr)))
+}
+
+fn quad_shape_decl(i: Span) -> IResult<Span, QuadPropertiesEnum> {
+    let (i, shape) = delimited(
+        space_delimited(tag("shape:")),
+        space_delimited(alt((
+            value(QuadShape::Parallelogram, tag("parallelogram")),
+            value(QuadShape::Triangle, tag("triangle")),
+        ))),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, QuadPropertiesEnum::Shape(shape)))
+}
+
+fn quad_material_decl(i: Span) -> IResult<Span, QuadPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, QuadPropertiesEnum::Material(expr)))
+}
+
+fn quad_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Quad"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut origin: Option<Expression> = None;
+    let mut u: Option<Expression> = None;
+    let mut v: Option<Expression> = None;
+    let mut shape = QuadShape::Parallelogram;
+    let mut material: Option<Expression> = None;
+
+    let (i, options) = many0(alt((
+        quad_origin_decl,
+        quad_u_decl,
+        quad_v_decl,
+        quad_shape_decl,
+        quad_material_decl,
+    )))(i)?;
+    options.into_iter().for_each(|option| match option {
+        QuadPropertiesEnum::Origin(expr) => origin = Some(expr),
+        QuadPropertiesEnum::U(expr) => u = Some(expr),
+        QuadPropertiesEnum::V(expr) => v = Some(expr),
+        QuadPropertiesEnum::Shape(s) => shape = s,
+        QuadPropertiesEnum::Material(expr) => material = Some(expr),
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    let (Some(origin), Some(u), Some(v), Some(material)) = (origin, u, v, material) else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::Quad {
+            origin,
+            u,
+            v,
+            shape,
+            material,
+            affine,
+        },
+    ))
+}
+
+#[derive(Debug)]
+enum TrianglePropertiesEnum<'src> {
+    Vertex((Expression<'src>, Expression<'src>, Expression<'src>)),
+    Material(Expression<'src>),
+}
+
+fn triangle_vertex_decl(i: Span) -> IResult<Span, TrianglePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("vertex:")),
+        space_delimited(|i| {
+            let (i, _) = tag("(")(i)?;
+            let (i, v1) = expr(i)?;
+            let (i, _) = tag(",")(i)?;
+            let (i, v2) = expr(i)?;
+            let (i, _) = tag(",")(i)?;
+            let (i, v3) = expr(i)?;
+            let (i, _) = tag(")")(i)?;
+            Ok((i, (v1, v2, v3)))
+        }),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, TrianglePropertiesEnum::Vertex(expr)))
+}
+
+fn triangle_material_decl(i: Span) -> IResult<Span, TrianglePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, TrianglePropertiesEnum::Material(expr)))
+}
+
+fn triangle_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Triangle"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut vertex: Option<(Expression, Expression, Expression)> = None;
+    let mut material: Option<Expression> = None;
+
+    let (i, options) = many0(alt((triangle_vertex_decl, triangle_material_decl)))(i)?;
+    options.into_iter().for_each(|option| match option {
+        TrianglePropertiesEnum::Vertex(v) => vertex = Some(v),
+        TrianglePropertiesEnum::Material(m) => material = Some(m),
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    let (Some(vertex), Some(material)) = (vertex, material) else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::Triangle {
+            vertex,
+            material,
+            affine,
+        },
+    ))
+}
+
+#[derive(Debug)]
+enum MeshPropertiesEnum<'src> {
+    Vertices(Expression<'src>),
+    Faces(Expression<'src>),
+    Normals(Expression<'src>),
+    Uvs(Expression<'src>),
+    Material(Expression<'src>),
+}
+
+fn mesh_vertices_decl(i: Span) -> IResult<Span, MeshPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("vertices:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, MeshPropertiesEnum::Vertices(expr)))
+}
+
+fn mesh_faces_decl(i: Span) -> IResult<Span, MeshPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("faces:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, MeshPropertiesEnum::Faces(expr)))
+}
+
+fn mesh_normals_decl(i: Span) -> IResult<Span, MeshPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("normals:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, MeshPropertiesEnum::Normals(expr)))
+}
+
+fn mesh_uvs_decl(i: Span) -> IResult<Span, MeshPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("uvs:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, MeshPropertiesEnum::Uvs(expr)))
+}
+
+fn mesh_material_decl(i: Span) -> IResult<Span, MeshPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, MeshPropertiesEnum::Material(expr)))
+}
+
+fn mesh_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Mesh"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut vertices: Option<Expression> = None;
+    let mut faces: Option<Expression> = None;
+    let mut normals: Option<Expression> = None;
+    let mut uvs: Option<Expression> = None;
+    let mut material: Option<Expression> = None;
+
+    let (i, options) = many0(alt((
+        mesh_vertices_decl,
+        mesh_faces_decl,
+        mesh_normals_decl,
+        mesh_uvs_decl,
+        mesh_material_decl,
+    )))(i)?;
+    options.into_iter().for_each(|option| match option {
+        MeshPropertiesEnum::Vertices(expr) => vertices = Some(expr),
+        MeshPropertiesEnum::Faces(expr) => faces = Some(expr),
+        MeshPropertiesEnum::Normals(expr) => normals = Some(expr),
+        MeshPropertiesEnum::Uvs(expr) => uvs = Some(expr),
+        MeshPropertiesEnum::Material(expr) => material = Some(expr),
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    let (Some(vertices), Some(faces), Some(material)) = (vertices, faces, material) else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::Mesh {
+            vertices,
+            faces,
+            normals,
+            uvs,
+            material,
+            affine,
+        },
+    ))
+}
+
+#[derive(Debug)]
+enum ShapePropertiesEnum<'src> {
+    Base(Expression<'src>),
+    Cap(Expression<'src>),
+    Radius(Expression<'src>),
+    BaseRadius(Expression<'src>),
+    CapRadius(Expression<'src>),
+    Center(Expression<'src>),
+    Normal(Expression<'src>),
+    Major(Expression<'src>),
+    Minor(Expression<'src>),
+    Open(bool),
+    Material(Expression<'src>),
+}
+
+impl ShapePropertiesEnum<'_> {
+    fn name(&self) -> &'static str {
+        match self {
+            ShapePropertiesEnum::Base(_) => "base",
+            ShapePropertiesEnum::Cap(_) => "cap",
+            ShapePropertiesEnum::Radius(_) => "radius",
+            ShapePropertiesEnum::BaseRadius(_) => "base_radius",
+            ShapePropertiesEnum::CapRadius(_) => "cap_radius",
+            ShapePropertiesEnum::Center(_) => "center",
+            ShapePropertiesEnum::Normal(_) => "normal",
+            ShapePropertiesEnum::Major(_) => "major",
+            ShapePropertiesEnum::Minor(_) => "minor",
+            ShapePropertiesEnum::Open(_) => "open",
+            ShapePropertiesEnum::Material(_) => "material",
+        }
+    }
+}
+
+fn shape_base_decl(i: Span) -> IResult<Span, ShapePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("base:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ShapePropertiesEnum::Base(expr)))
+}
+
+fn shape_cap_decl(i: Span) -> IResult<Span, ShapePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("cap:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ShapePropertiesEnum::Cap(expr)))
+}
+
+fn shape_radius_decl(i: Span) -> IResult<Span, ShapePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("radius:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ShapePropertiesEnum::Radius(expr)))
+}
+
+fn shape_base_radius_decl(i: Span) -> IResult<Span, ShapePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("base_radius:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ShapePropertiesEnum::BaseRadius(expr)))
+}
+
+fn shape_cap_radius_decl(i: Span) -> IResult<Span, ShapePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("cap_radius:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ShapePropertiesEnum::CapRadius(expr)))
+}
+
+fn shape_center_decl(i: Span) -> IResult<Span, ShapePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("center:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ShapePropertiesEnum::Center(expr)))
+}
+
+fn shape_normal_decl(i: Span) -> IResult<Span, ShapePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("normal:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ShapePropertiesEnum::Normal(expr)))
+}
+
+fn shape_major_decl(i: Span) -> IResult<Span, ShapePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("major:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ShapePropertiesEnum::Major(expr)))
+}
+
+fn shape_minor_decl(i: Span) -> IResult<Span, ShapePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("minor:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ShapePropertiesEnum::Minor(expr)))
+}
+
+fn shape_open_decl(i: Span) -> IResult<Span, ShapePropertiesEnum> {
+    let (i, open) = delimited(
+        space_delimited(tag("open:")),
+        space_delimited(alt((value(true, tag("true")), value(false, tag("false"))))),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ShapePropertiesEnum::Open(open)))
+}
+
+fn shape_material_decl(i: Span) -> IResult<Span, ShapePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ShapePropertiesEnum::Material(expr)))
+}
+
+#[derive(Default)]
+struct ShapeProperties<'src> {
+    base: Option<Expression<'src>>,
+    cap: Option<Expression<'src>>,
+    radius: Option<Expression<'src>>,
+    base_radius: Option<Expression<'src>>,
+    cap_radius: Option<Expression<'src>>,
+    center: Option<Expression<'src>>,
+    normal: Option<Expression<'src>>,
+    major: Option<Expression<'src>>,
+    minor: Option<Expression<'src>>,
+    open: bool,
+    material: Option<Expression<'src>>,
+    affine: Vec<AffineProperties<'src>>,
+}
+
+/// A missing property, or one the object does not take.
+fn shape_error(i: Span) -> nom::Err<nom::error::Error<Span>> {
+    nom::Err::Error(nom::error::Error {
+        input: i,
+        code: nom::error::ErrorKind::Tag,
+    })
+}
+
+/// Properties of the round objects, each of which takes the ones in `allowed`.
+fn general_shape_object_properties<'a>(
+    i: Span<'a>,
+    allowed: &[&str],
+) -> IResult<Span<'a>, ShapeProperties<'a>> {
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let (i, options) = many0(alt((
+        shape_base_radius_decl,
+        shape_base_decl,
+        shape_cap_radius_decl,
+        shape_cap_decl,
+        shape_radius_decl,
+        shape_center_decl,
+        shape_normal_decl,
+        shape_major_decl,
+        shape_minor_decl,
+        shape_open_decl,
+        shape_material_decl,
+    )))(i)?;
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    let mut properties = ShapeProperties {
+        affine,
+        ..Default::default()
+    };
+    for option in options {
+        if !allowed.contains(&option.name()) {
+            return Err(shape_error(i));
+        }
+        match option {
+            ShapePropertiesEnum::Base(expr) => properties.base = Some(expr),
+            ShapePropertiesEnum::Cap(expr) => properties.cap = Some(expr),
+            ShapePropertiesEnum::Radius(expr) => properties.radius = Some(expr),
+            ShapePropertiesEnum::BaseRadius(expr) => properties.base_radius = Some(expr),
+            ShapePropertiesEnum::CapRadius(expr) => properties.cap_radius = Some(expr),
+            ShapePropertiesEnum::Center(expr) => properties.center = Some(expr),
+            ShapePropertiesEnum::Normal(expr) => properties.normal = Some(expr),
+            ShapePropertiesEnum::Major(expr) => properties.major = Some(expr),
+            ShapePropertiesEnum::Minor(expr) => properties.minor = Some(expr),
+            ShapePropertiesEnum::Open(open) => properties.open = open,
+            ShapePropertiesEnum::Material(expr) => properties.material = Some(expr),
+        }
+    }
+    Ok((i, properties))
+}
+
+fn cylinder_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Cylinder"))(i)?;
+    let (i, p) =
+        general_shape_object_properties(i, &["base", "cap", "radius", "open", "material"])?;
+    let (Some(base), Some(cap), Some(radius), Some(material)) =
+        (p.base, p.cap, p.radius, p.material)
+    else {
+        return Err(shape_error(i));
+    };
+    Ok((
+        i,
+        Object::Cylinder {
+            base,
+            cap,
+            radius,
+            open: p.open,
+            material,
+            affine: p.affine,
+        },
+    ))
+}
+
+fn cone_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Cone"))(i)?;
+    let (i, p) = general_shape_object_properties(
+        i,
+        &["base", "base_radius", "cap", "cap_radius", "material"],
+    )?;
+    let (Some(base), Some(base_radius), Some(cap), Some(cap_radius), Some(material)) =
+        (p.base, p.base_radius, p.cap, p.cap_radius, p.material)
+    else {
+        return Err(shape_error(i));
+    };
+    Ok((
+        i,
+        Object::Cone {
+            base,
+            base_radius,
+            cap,
+            cap_radius,
+            material,
+            affine: p.affine,
+        },
+    ))
+}
+
+fn disk_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Disk"))(i)?;
+    let (i, p) = general_shape_object_properties(i, &["center", "normal", "radius", "material"])?;
+    let (Some(center), Some(normal), Some(radius), Some(material)) =
+        (p.center, p.normal, p.radius, p.material)
+    else {
+        return Err(shape_error(i));
+    };
+    Ok((
+        i,
+        Object::Disk {
+            center,
+            normal,
+            radius,
+            material,
+            affine: p.affine,
+        },
+    ))
+}
+
+fn torus_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Torus"))(i)?;
+    let (i, p) = general_shape_object_properties(i, &["major", "minor", "material"])?;
+    let (Some(major), Some(minor), Some(material)) = (p.major, p.minor, p.material) else {
+        return Err(shape_error(i));
+    };
+    Ok((
+        i,
+        Object::Torus {
+            major,
+            minor,
+            material,
+            affine: p.affine,
+        },
+    ))
+}
+
+#[derive(Debug)]
+enum ModelPropertiesEnum<'src> {
+    File(Expression<'src>),
+    Material(Expression<'src>),
+    Normalize(bool),
+}
+
+fn model_file_decl(i: Span) -> IResult<Span, ModelPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("file:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ModelPropertiesEnum::File(expr)))
+}
+
+fn model_material_decl(i: Span) -> IResult<Span, ModelPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ModelPropertiesEnum::Material(expr)))
+}
+
+fn model_normalize_decl(i: Span) -> IResult<Span, ModelPropertiesEnum> {
+    let (i, normalize) = delimited(
+        space_delimited(tag("normalize:")),
+        space_delimited(alt((value(true, tag("true")), value(false, tag("false"))))),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ModelPropertiesEnum::Normalize(normalize)))
+}
+
+fn model_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Model"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut file: Option<Expression> = None;
+    let mut material: Option<Expression> = None;
+    let mut normalize = false;
+
+    let (i, options) = many0(alt((
+        model_file_decl,
+        model_material_decl,
+        model_normalize_decl,
+    )))(i)?;
+    options.into_iter().for_each(|option| match option {
+        ModelPropertiesEnum::File(expr) => file = Some(expr),
+        ModelPropertiesEnum::Material(expr) => material = Some(expr),
+        ModelPropertiesEnum::Normalize(b) => normalize = b,
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    let Some(file) = file else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::Model {
+            file,
+            material,
+            normalize,
+            affine,
+        },
+    ))
+}
+
+#[derive(Debug)]
+enum HeightFieldPropertiesEnum<'src> {
+    Image(Expression<'src>),
+    Function(Expression<'src>),
+    Resolution(Expression<'src>),
+    Size(Expression<'src>),
+    Material(Expression<'src>),
+}
+
+fn height_field_image_decl(i: Span) -> IResult<Span, HeightFieldPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("image:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, HeightFieldPropertiesEnum::Image(expr)))
+}
+
+fn height_field_function_decl(i: Span) -> IResult<Span, HeightFieldPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("function:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, HeightFieldPropertiesEnum::Function(expr)))
+}
+
+fn height_field_resolution_decl(i: Span) -> IResult<Span, HeightFieldPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("resolution:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, HeightFieldPropertiesEnum::Resolution(expr)))
+}
+
+fn height_field_size_decl(i: Span) -> IResult<Span, HeightFieldPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("size:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, HeightFieldPropertiesEnum::Size(expr)))
+}
+
+fn height_field_material_decl(i: Span) -> IResult<Span, HeightFieldPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, HeightFieldPropertiesEnum::Material(expr)))
+}
+
+fn height_field_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("HeightField"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut image: Option<Expression> = None;
+    let mut function: Option<Expression> = None;
+    let mut resolution: Option<Expression> = None;
+    let mut size: Option<Expression> = None;
+    let mut material: Option<Expression> = None;
+
+    let (i, options) = many0(alt((
+        height_field_image_decl,
+        height_field_function_decl,
+        height_field_resolution_decl,
+        height_field_size_decl,
+        height_field_material_decl,
+    )))(i)?;
+    options.into_iter().for_each(|option| match option {
+        HeightFieldPropertiesEnum::Image(expr) => image = Some(expr),
+        HeightFieldPropertiesEnum::Function(expr) => function = Some(expr),
+        HeightFieldPropertiesEnum::Resolution(expr) => resolution = Some(expr),
+        HeightFieldPropertiesEnum::Size(expr) => size = Some(expr),
+        HeightFieldPropertiesEnum::Material(expr) => material = Some(expr),
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    // exactly one source, and a resolution only for a function
+    let source = match (image, function, resolution) {
+        (Some(image), None, None) => Some(HeightSource::Image(image)),
+        (None, Some(function), resolution) => Some(HeightSource::Function {
+            function,
+            resolution,
+        }),
+        _ => None,
+    };
+    let (Some(source), Some(material)) = (source, material) else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::HeightField {
+            source,
+            size,
+            material,
+            affine,
+        },
+    ))
+}
+
+fn spline_decl(i: Span) -> IResult<Span, Interpolation> {
+    let (i, spline) = delimited(
+        space_delimited(tag("spline:")),
+        space_delimited(alt((
+            value(Interpolation::Linear, tag("linear")),
+            value(Interpolation::Cubic, tag("cubic")),
+        ))),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, spline))
+}
+
+#[derive(Debug)]
+enum LathePropertiesEnum<'src> {
+    Points(Expression<'src>),
+    Spline(Interpolation),
+    Segments(Expression<'src>),
+    Material(Expression<'src>),
+}
+
+fn lathe_points_decl(i: Span) -> IResult<Span, LathePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("points:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, LathePropertiesEnum::Points(expr)))
+}
+
+fn lathe_spline_decl(i: Span) -> IResult<Span, LathePropertiesEnum> {
+    let (i, spline) = spline_decl(i)?;
+    Ok((i, LathePropertiesEnum::Spline(spline)))
+}
+
+fn lathe_segments_decl(i: Span) -> IResult<Span, LathePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("segments:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, LathePropertiesEnum::Segments(expr)))
+}
+
+fn lathe_material_decl(i: Span) -> IResult<Span, LathePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, LathePropertiesEnum::Material(expr)))
+}
+
+fn lathe_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Lathe"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut points: Option<Expression> = None;
+    let mut spline = Interpolation::Linear;
+    let mut segments: Option<Expression> = None;
+    let mut material: Option<Expression> = None;
+
+    let (i, options) = many0(alt((
+        lathe_points_decl,
+        lathe_spline_decl,
+        lathe_segments_decl,
+        lathe_material_decl,
+    )))(i)?;
+    options.into_iter().for_each(|option| match option {
+        LathePropertiesEnum::Points(expr) => points = Some(expr),
+        LathePropertiesEnum::Spline(s) => spline = s,
+        LathePropertiesEnum::Segments(expr) => segments = Some(expr),
+        LathePropertiesEnum::Material(expr) => material = Some(expr),
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    let (Some(points), Some(material)) = (points, material) else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::Lathe {
+            points,
+            spline,
+            segments,
+            material,
+            affine,
+        },
+    ))
+}
+
+#[derive(Debug)]
+enum SweepPropertiesEnum<'src> {
+    Path(Expression<'src>),
+    Profile(Expression<'src>),
+    Radius(Expression<'src>),
+    Segments(Expression<'src>),
+    Spline(Interpolation),
+    Material(Expression<'src>),
+}
+
+fn sweep_path_decl(i: Span) -> IResult<Span, SweepPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("path:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, SweepPropertiesEnum::Path(expr)))
+}
+
+fn sweep_profile_decl(i: Span) -> IResult<Span, SweepPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("profile:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, SweepPropertiesEnum::Profile(expr)))
+}
+
+fn sweep_radius_decl(i: Span) -> IResult<Span, SweepPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("radius:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, SweepPropertiesEnum::Radius(expr)))
+}
+
+fn sweep_segments_decl(i: Span) -> IResult<Span, SweepPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("segments:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, SweepPropertiesEnum::Segments(expr)))
+}
+
+fn sweep_spline_decl(i: Span) -> IResult<Span, SweepPropertiesEnum> {
+    let (i, spline) = spline_decl(i)?;
+    Ok((i, SweepPropertiesEnum::Spline(spline)))
+}
+
+fn sweep_material_decl(i: Span) -> IResult<Span, SweepPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, SweepPropertiesEnum::Material(expr)))
+}
+
+fn sweep_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Sweep"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut path: Option<Expression> = None;
+    let mut points: Option<Expression> = None;
+    let mut radius: Option<Expression> = None;
+    let mut segments: Option<Expression> = None;
+    let mut spline = Interpolation::Linear;
+    let mut material: Option<Expression> = None;
+
+    let (i, options) = many0(alt((
+        sweep_path_decl,
+        sweep_profile_decl,
+        sweep_radius_decl,
+        sweep_segments_decl,
+        sweep_spline_decl,
+        sweep_material_decl,
+    )))(i)?;
+    options.into_iter().for_each(|option| match option {
+        SweepPropertiesEnum::Path(expr) => path = Some(expr),
+        SweepPropertiesEnum::Profile(expr) => points = Some(expr),
+        SweepPropertiesEnum::Radius(expr) => radius = Some(expr),
+        SweepPropertiesEnum::Segments(expr) => segments = Some(expr),
+        SweepPropertiesEnum::Spline(s) => spline = s,
+        SweepPropertiesEnum::Material(expr) => material = Some(expr),
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    // exactly one profile, and segments only for a circle
+    let profile = match (points, radius, segments) {
+        (Some(points), None, None) => Some(SweepProfile::Points(points)),
+        (None, Some(radius), segments) => Some(SweepProfile::Circle { radius, segments }),
+        _ => None,
+    };
+    let (Some(path), Some(profile), Some(material)) = (path, profile, material) else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::Sweep {
+            path,
+            profile,
+            spline,
+            material,
+            affine,
+        },
+    ))
+}
+
+#[derive(Debug)]
+enum BezierPatchPropertiesEnum<'src> {
+    Control(Expression<'src>),
+    File(Expression<'src>),
+    Steps(Expression<'src>),
+    Material(Expression<'src>),
+}
+
+fn bezier_patch_control_decl(i: Span) -> IResult<Span, BezierPatchPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("control:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, BezierPatchPropertiesEnum::Control(expr)))
+}
+
+fn bezier_patch_file_decl(i: Span) -> IResult<Span, BezierPatchPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("file:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, BezierPatchPropertiesEnum::File(expr)))
+}
+
+fn bezier_patch_steps_decl(i: Span) -> IResult<Span, BezierPatchPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("steps:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, BezierPatchPropertiesEnum::Steps(expr)))
+}
+
+fn bezier_patch_material_decl(i: Span) -> IResult<Span, BezierPatchPropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, BezierPatchPropertiesEnum::Material(expr)))
+}
+
+fn bezier_patch_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("BezierPatch"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut control: Option<Expression> = None;
+    let mut file: Option<Expression> = None;
+    let mut steps: Option<Expression> = None;
+    let mut material: Option<Expression> = None;
+
+    let (i, options) = many0(alt((
+        bezier_patch_control_decl,
+        bezier_patch_file_decl,
+        bezier_patch_steps_decl,
+        bezier_patch_material_decl,
+    )))(i)?;
+    options.into_iter().for_each(|option| match option {
+        BezierPatchPropertiesEnum::Control(expr) => control = Some(expr),
+        BezierPatchPropertiesEnum::File(expr) => file = Some(expr),
+        BezierPatchPropertiesEnum::Steps(expr) => steps = Some(expr),
+        BezierPatchPropertiesEnum::Material(expr) => material = Some(expr),
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    // exactly one source
+    let source = match (control, file) {
+        (Some(control), None) => Some(PatchSource::Control(control)),
+        (None, Some(file)) => Some(PatchSource::File(file)),
+        _ => None,
+    };
+    let (Some(source), Some(material)) = (source, material) else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::BezierPatch {
+            source,
+            steps,
+            material,
+            affine,
+        },
+    ))
+}
+
+#[derive(Debug)]
+enum IsosurfacePropertiesEnum<'src> {
+    Function(Expression<'src>),
+    Bounds((Expression<'src>, Expression<'src>)),
+    Threshold(Expression<'src>),
+    MaxGradient(Expression<'src>),
+    Material(Expression<'src>),
+}
+
+fn isosurface_function_decl(i: Span) -> IResult<Span, IsosurfacePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("function:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, IsosurfacePropertiesEnum::Function(expr)))
+}
+
+fn isosurface_bounds_decl(i: Span) -> IResult<Span, IsosurfacePropertiesEnum> {
+    let (i, bounds) = delimited(
+        space_delimited(tag("bounds:")),
+        space_delimited(|i| {
+            let (i, _) = tag("(")(i)?;
+            let (i, min) = expr(i)?;
+            let (i, _) = tag(",")(i)?;
+            let (i, max) = expr(i)?;
+            let (i, _) = tag(")")(i)?;
+            Ok((i, (min, max)))
+        }),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, IsosurfacePropertiesEnum::Bounds(bounds)))
+}
+
+fn isosurface_threshold_decl(i: Span) -> IResult<Span, IsosurfacePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("threshold:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, IsosurfacePropertiesEnum::Threshold(expr)))
+}
+
+fn isosurface_max_gradient_decl(i: Span) -> IResult<Span, IsosurfacePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("max_gradient:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, IsosurfacePropertiesEnum::MaxGradient(expr)))
+}
+
+fn isosurface_material_decl(i: Span) -> IResult<Span, IsosurfacePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, IsosurfacePropertiesEnum::Material(expr)))
+}
+
+fn isosurface_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Isosurface"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut function: Option<Expression> = None;
+    let mut bounds: Option<(Expression, Expression)> = None;
+    let mut threshold: Option<Expression> = None;
+    let mut max_gradient: Option<Expression> = None;
+    let mut material: Option<Expression> = None;
+
+    let (i, options) = many0(alt((
+        isosurface_function_decl,
+        isosurface_bounds_decl,
+        isosurface_threshold_decl,
+        isosurface_max_gradient_decl,
+        isosurface_material_decl,
+    )))(i)?;
+    options.into_iter().for_each(|option| match option {
+        IsosurfacePropertiesEnum::Function(expr) => function = Some(expr),
+        IsosurfacePropertiesEnum::Bounds(b) => bounds = Some(b),
+        IsosurfacePropertiesEnum::Threshold(expr) => threshold = Some(expr),
+        IsosurfacePropertiesEnum::MaxGradient(expr) => max_gradient = Some(expr),
+        IsosurfacePropertiesEnum::Material(expr) => material = Some(expr),
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    let (Some(function), Some(bounds), Some(material)) = (function, bounds, material) else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::Isosurface {
+            function,
+            bounds,
+            threshold,
+            max_gradient,
+            material,
+            affine,
+        },
+    ))
+}
+
+#[derive(Debug)]
+enum InstancePropertiesEnum<'src> {
+    Of(Expression<'src>),
+    Material(Expression<'src>),
+}
+
+fn instance_of_decl(i: Span) -> IResult<Span, InstancePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("of:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, InstancePropertiesEnum::Of(expr)))
+}
+
+fn instance_material_decl(i: Span) -> IResult<Span, InstancePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("material:")),
+        space_delimited(material_expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, InstancePropertiesEnum::Material(expr)))
+}
+
+fn instance_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Instance"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut of: Option<Expression> = None;
+    let mut material: Option<Expression> = None;
+
+    let (i, options) = many0(alt((instance_of_decl, instance_material_decl)))(i)?;
+    options.into_iter().for_each(|option| match option {
+        InstancePropertiesEnum::Of(expr) => of = Some(expr),
+        InstancePropertiesEnum::Material(expr) => material = Some(expr),
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    let Some(of) = of else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::Instance {
+            of,
+            material,
+            affine,
+        },
+    ))
+}
+
+#[derive(Debug)]
+enum VolumePropertiesEnum<'src> {
+    Boundary(Box<Object<'src>>),
+    Density(Expression<'src>),
+    Color(Expression<'src>),
+    Noise(Expression<'src>),
+}
+
+fn volume_boundary_decl(i: Span) -> IResult<Span, VolumePropertiesEnum> {
+    let (i, boundary) = delimited(
+        space_delimited(tag("boundary:")),
+        object,
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, VolumePropertiesEnum::Boundary(Box::new(boundary))))
+}
+
+fn volume_density_decl(i: Span) -> IResult<Span, VolumePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("density:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, VolumePropertiesEnum::Density(expr)))
+}
+
+fn volume_color_decl(i: Span) -> IResult<Span, VolumePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("color:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, VolumePropertiesEnum::Color(expr)))
+}
+
+fn volume_noise_decl(i: Span) -> IResult<Span, VolumePropertiesEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("noise:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, VolumePropertiesEnum::Noise(expr)))
+}
+
+fn volume_object(i: Span) -> IResult<Span, Object> {
+    let (i, _) = space_delimited(tag("Volume"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+
+    let mut boundary: Option<Box<Object>> = None;
+    let mut density: Option<Expression> = None;
+    let mut color: Option<Expression> = None;
+    let mut noise: Option<Expression> = None;
+
+    let (i, options) = many0(alt((
+        volume_boundary_decl,
+        volume_density_decl,
+        volume_color_decl,
+        volume_noise_decl,
+    )))(i)?;
+    options.into_iter().for_each(|option| match option {
+        VolumePropertiesEnum::Boundary(object) => boundary = Some(object),
+        VolumePropertiesEnum::Density(expr) => density = Some(expr),
+        VolumePropertiesEnum::Color(expr) => color = Some(expr),
+        VolumePropertiesEnum::Noise(expr) => noise = Some(expr),
+    });
+    let (i, affine) = affine_properties(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+
+    let (Some(boundary), Some(density), Some(color)) = (boundary, density, color) else {
+        return Err(nom::Err::Error(nom::error::Error {
+            input: i,
+            code: nom::error::ErrorKind::Tag,
+        }));
+    };
+    Ok((
+        i,
+        Object::Volume {
+            boundary,
+            density,
+            color,
+            noise,
+            affine,
+        },
+    ))
+}
+
 pub(super) fn object(i: Span) -> IResult<Span, Object> {
-    alt((sphere_object, box_object, plane_object, objects))(i)
+    alt((
+        sphere_object,
+        blob_object,
+        box_object,
+        plane_object,
+        quad_object,
+        triangle_object,
+        mesh_object,
+        cylinder_object,
+        cone_object,
+        disk_object,
+        torus_object,
+        model_object,
+        height_field_object,
+        lathe_object,
+        sweep_object,
+        bezier_patch_object,
+        isosurface_object,
+        csg_object,
+        volume_object,
+        instance_object,
+        objects,
+    ))(i)
 }
@@ -1,21 +1,23 @@
 use nom::{
     branch::alt,
-    bytes::complete::tag,
+    bytes::complete::{tag, take_until},
     character::complete::{char, multispace0},
-    combinator::{cut, map_res, opt},
-    multi::many0,
+    combinator::{cut, map_res, opt, value},
+    multi::{many0, many1},
     sequence::{delimited, pair, preceded, terminated},
     Finish, IResult,
 };
 
 use super::{
     calc_offset, close_brace,
-    expression::{comment_expr, expr, vec3_ident_expr},
+    expression::{comment_expr, expr},
     identifier,
     object::object,
     open_brace, space_delimited,
 };
-use crate::ast::{CameraConfig, Config, Expression, Span, Statement, AST};
+use crate::ast::{
+    camera::Projection, config::Fog, CameraConfig, Config, Expression, Span, Statement, AST,
+};
 
 fn object_statement(i0: Span) -> IResult<Span, Statement> {
     let (i, object) = object(i0)?;
@@ -27,18 +29,45 @@ fn object_statement(i0: Span) -> IResult<Span, Statement> {
         },
     ))
 }
+
+fn prototype_statement(i0: Span) -> IResult<Span, Statement> {
+    let (i, _) = space_delimited(tag("Prototype"))(i0)?;
+    let (i, name) = space_delimited(identifier)(i)?;
+    let (i, objects) = delimited(
+        space_delimited(open_brace),
+        many1(object),
+        space_delimited(close_brace),
+    )(i)?;
+    Ok((
+        i,
+        Statement::Prototype {
+            span: calc_offset(i0, i),
+            name,
+            objects,
+        },
+    ))
+}
 enum CameraConfigEnum<'a> {
     Lookfrom(Expression<'a>),
     Lookat(Expression<'a>),
     Up(Expression<'a>),
     Angle(Expression<'a>),
     DistToFocus(Expression<'a>),
+    FocusOn(Expression<'a>),
+    Aperture(Expression<'a>),
+    FStop(Expression<'a>),
+    ShutterOpen(Expression<'a>),
+    ShutterClose(Expression<'a>),
+    Projection(Projection),
+    OrthoWidth(Expression<'a>),
+    AutoFrame(bool),
+    Direction(Expression<'a>),
 }
 
 fn loockfrom_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
     let (i, expr) = delimited(
         space_delimited(tag("lookfrom:")),
-        space_delimited(vec3_ident_expr),
+        space_delimited(expr),
         space_delimited(tag(",")),
     )(i)?;
     let (i, _) = opt(space_delimited(comment_expr))(i)?;
@@ -48,7 +77,7 @@ fn loockfrom_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
 fn loockat_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
     let (i, expr) = delimited(
         space_delimited(tag("lookat:")),
-        space_delimited(vec3_ident_expr),
+        space_delimited(expr),
         space_delimited(tag(",")),
     )(i)?;
     let (i, _) = opt(space_delimited(comment_expr))(i)?;
@@ -58,7 +87,7 @@ fn loockat_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
 fn up_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
     let (i, expr) = delimited(
         space_delimited(tag("up:")),
-        space_delimited(vec3_ident_expr),
+        space_delimited(expr),
         space_delimited(tag(",")),
     )(i)?;
     let (i, _) = opt(space_delimited(comment_expr))(i)?;
@@ -85,8 +114,108 @@ fn dist_to_focus_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
     Ok((i, CameraConfigEnum::DistToFocus(expr)))
 }
 
+fn focus_on_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("focus_on:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, CameraConfigEnum::FocusOn(expr)))
+}
+
+fn aperture_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("aperture:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, CameraConfigEnum::Aperture(expr)))
+}
+
+fn f_stop_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("f_stop:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, CameraConfigEnum::FStop(expr)))
+}
+
+fn shutter_open_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("shutter_open:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, CameraConfigEnum::ShutterOpen(expr)))
+}
+
+fn shutter_close_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("shutter_close:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, CameraConfigEnum::ShutterClose(expr)))
+}
+
+fn projection_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
+    let (i, projection) = delimited(
+        space_delimited(tag("projection:")),
+        space_delimited(alt((
+            value(Projection::Perspective, tag("perspective")),
+            value(Projection::Orthographic, tag("orthographic")),
+            value(Projection::Fisheye, tag("fisheye")),
+            value(Projection::Equirect, tag("equirect")),
+        ))),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, CameraConfigEnum::Projection(projection)))
+}
+
+fn ortho_width_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("ortho_width:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, CameraConfigEnum::OrthoWidth(expr)))
+}
+
+fn auto_frame_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
+    let (i, auto_frame) = delimited(
+        space_delimited(tag("auto_frame:")),
+        space_delimited(alt((value(true, tag("true")), value(false, tag("false"))))),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, CameraConfigEnum::AutoFrame(auto_frame)))
+}
+
+fn direction_decl(i: Span) -> IResult<Span, CameraConfigEnum> {
+    let (i, expr) = delimited(
+        space_delimited(tag("direction:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, CameraConfigEnum::Direction(expr)))
+}
+
 fn camera_statement(i: Span) -> IResult<Span, Statement> {
     let (i, _) = space_delimited(tag("Camera"))(i)?;
+    let (i, name) = opt(space_delimited(delimited(
+        char('"'),
+        take_until("\""),
+        char('"'),
+    )))(i)?;
     let (i, _) = space_delimited(open_brace)(i)?;
 
     let mut lookfrom: Option<Expression> = None;
@@ -94,6 +223,15 @@ fn camera_statement(i: Span) -> IResult<Span, Statement> {
     let mut up: Option<Expression> = None;
     let mut angle: Option<Expression> = None;
     let mut dist_to_focus: Option<Expression> = None;
+    let mut focus_on: Option<Expression> = None;
+    let mut aperture: Option<Expression> = None;
+    let mut f_stop: Option<Expression> = None;
+    let mut shutter_open: Option<Expression> = None;
+    let mut shutter_close: Option<Expression> = None;
+    let mut projection = Projection::Perspective;
+    let mut ortho_width: Option<Expression> = None;
+    let mut auto_frame = false;
+    let mut direction: Option<Expression> = None;
     let i0 = i;
 
     let (i, p) = many0(alt((
@@ -102,6 +240,15 @@ fn camera_statement(i: Span) -> IResult<Span, Statement> {
         up_decl,
         angle_decl,
         dist_to_focus_decl,
+        focus_on_decl,
+        aperture_decl,
+        f_stop_decl,
+        shutter_open_decl,
+        shutter_close_decl,
+        projection_decl,
+        ortho_width_decl,
+        auto_frame_decl,
+        direction_decl,
     )))(i)?;
 
     p.iter().for_each(|v| match v {
@@ -110,9 +257,18 @@ fn camera_statement(i: Span) -> IResult<Span, Statement> {
         CameraConfigEnum::Up(expr) => up = Some(expr.clone()),
         CameraConfigEnum::Angle(expr) => angle = Some(expr.clone()),
         CameraConfigEnum::DistToFocus(expr) => dist_to_focus = Some(expr.clone()),
+        CameraConfigEnum::FocusOn(expr) => focus_on = Some(expr.clone()),
+        CameraConfigEnum::Aperture(expr) => aperture = Some(expr.clone()),
+        CameraConfigEnum::FStop(expr) => f_stop = Some(expr.clone()),
+        CameraConfigEnum::ShutterOpen(expr) => shutter_open = Some(expr.clone()),
+        CameraConfigEnum::ShutterClose(expr) => shutter_close = Some(expr.clone()),
+        CameraConfigEnum::Projection(p) => projection = *p,
+        CameraConfigEnum::OrthoWidth(expr) => ortho_width = Some(expr.clone()),
+        CameraConfigEnum::AutoFrame(b) => auto_frame = *b,
+        CameraConfigEnum::Direction(expr) => direction = Some(expr.clone()),
     });
 
-    if lookfrom.is_none() || lookat.is_none() || angle.is_none() {
+    if (!auto_frame && (lookfrom.is_none() || lookat.is_none())) || angle.is_none() {
         return Err(nom::Err::Error(nom::error::Error {
             input: i,
             code: nom::error::ErrorKind::Tag,
@@ -125,13 +281,23 @@ fn camera_statement(i: Span) -> IResult<Span, Statement> {
         i,
         (Statement::Camera {
             span: calc_offset(i0, i),
-            config: CameraConfig {
-                lookfrom: lookfrom.unwrap(),
-                lookat: lookat.unwrap(),
+            name,
+            config: Box::new(CameraConfig {
+                lookfrom,
+                lookat,
+                auto_frame,
+                direction,
                 up,
                 angle: angle.unwrap(),
                 dist_to_focus,
-            },
+                focus_on,
+                aperture,
+                f_stop,
+                shutter_open,
+                shutter_close,
+                projection,
+                ortho_width,
+            }),
         }),
     ))
 }
@@ -143,6 +309,7 @@ enum ConfigEnum<'a> {
     SamplesPerPixel(Expression<'a>),
     MaxDepth(Expression<'a>),
     Background(Expression<'a>),
+    Fog(Fog<'a>),
 }
 
 fn width_decl(i: Span) -> IResult<Span, ConfigEnum> {
@@ -188,13 +355,32 @@ fn max_depth_decl(i: Span) -> IResult<Span, ConfigEnum> {
 fn background_decl(i: Span) -> IResult<Span, ConfigEnum> {
     let (i, expr) = delimited(
         space_delimited(tag("background:")),
-        space_delimited(vec3_ident_expr),
+        space_delimited(expr),
         space_delimited(tag(",")),
     )(i)?;
     let (i, _) = opt(space_delimited(comment_expr))(i)?;
     Ok((i, ConfigEnum::Background(expr)))
 }
 
+fn fog_decl(i: Span) -> IResult<Span, ConfigEnum> {
+    let (i, _) = space_delimited(tag("fog:"))(i)?;
+    let (i, _) = space_delimited(open_brace)(i)?;
+    let (i, color) = delimited(
+        space_delimited(tag("color:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, density) = delimited(
+        space_delimited(tag("density:")),
+        space_delimited(expr),
+        space_delimited(tag(",")),
+    )(i)?;
+    let (i, _) = space_delimited(close_brace)(i)?;
+    let (i, _) = space_delimited(tag(","))(i)?;
+    let (i, _) = opt(space_delimited(comment_expr))(i)?;
+    Ok((i, ConfigEnum::Fog(Fog { color, density })))
+}
+
 fn config_statement(i: Span) -> IResult<Span, Statement> {
     let (i, _) = space_delimited(tag("Config"))(i)?;
     let (i, _) = space_delimited(open_brace)(i)?;
@@ -204,6 +390,7 @@ fn config_statement(i: Span) -> IResult<Span, Statement> {
     let mut samples_per_pixel: Option<Expression> = None;
     let mut max_depth: Option<Expression> = None;
     let mut background: Option<Expression> = None;
+    let mut fog: Option<Fog> = None;
     let i0 = i;
 
     let (i, p) = many0(alt((
@@ -212,6 +399,7 @@ fn config_statement(i: Span) -> IResult<Span, Statement> {
         samples_per_pixel_decl,
         max_depth_decl,
         background_decl,
+        fog_decl,
     )))(i)?;
 
     p.iter().for_each(|v| match v {
@@ -220,6 +408,7 @@ fn config_statement(i: Span) -> IResult<Span, Statement> {
         ConfigEnum::SamplesPerPixel(expr) => samples_per_pixel = Some(expr.clone()),
         ConfigEnum::MaxDepth(expr) => max_depth = Some(expr.clone()),
         ConfigEnum::Background(expr) => background = Some(expr.clone()),
+        ConfigEnum::Fog(f) => fog = Some(f.clone()),
     });
 
     if samples_per_pixel.is_none() || width.is_none() || height.is_none() {
@@ -241,6 +430,7 @@ fn config_statement(i: Span) -> IResult<Span, Statement> {
                 samples_per_pixel: samples_per_pixel.unwrap(),
                 max_depth,
                 background,
+                fog,
             },
         }),
     ))
@@ -262,6 +452,22 @@ fn var_assign(i: Span) -> IResult<Span, Statement> {
     ))
 }
 
+fn var_default(i: Span) -> IResult<Span, Statement> {
+    let span = i;
+    let (i, name) = space_delimited(identifier)(i)?;
+    let (i, _) = space_delimited(tag("?="))(i)?;
+    let (i, ex) = space_delimited(expr)(i)?;
+    let (i, _) = space_delimited(char(';'))(i)?;
+    Ok((
+        i,
+        Statement::VarDefault {
+            span: calc_offset(span, i),
+            name,
+            ex,
+        },
+    ))
+}
+
 fn expr_statement(i: Span) -> IResult<Span, Statement> {
     let (i, res) = expr(i)?;
     Ok((i, Statement::Expression(res)))
@@ -329,9 +535,11 @@ fn comment_statement(i: Span) -> IResult<Span, Statement> {
 pub fn statement(i: Span) -> IResult<Span, Statement> {
     alt((
         object_statement,
+        prototype_statement,
         camera_statement,
         config_statement,
         var_assign,
+        var_default,
         if_statement,
         while_statement,
         terminated(break_statement, pair(tag(";"), multispace0)),
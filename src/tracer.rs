@@ -0,0 +1,9 @@
+pub mod aabb;
+pub mod camera;
+pub mod hittable;
+pub mod material;
+mod perlin;
+pub mod ray;
+mod roots;
+pub mod texture;
+pub mod vec3;
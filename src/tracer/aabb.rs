@@ -0,0 +1,67 @@
+use super::{ray::Ray, vec3::Point3};
+
+/// Axis-aligned bounding box.
+#[derive(Debug, Clone, Copy)]
+pub struct AABB {
+    minimum: Point3,
+    maximum: Point3,
+}
+
+impl AABB {
+    pub fn new(minimum: Point3, maximum: Point3) -> Self {
+        AABB { minimum, maximum }
+    }
+
+    pub fn min(&self) -> Point3 {
+        self.minimum
+    }
+
+    pub fn max(&self) -> Point3 {
+        self.maximum
+    }
+
+    /// Whether `ray` passes through the box between `t_min` and `t_max`.
+    pub fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> bool {
+        self.range(ray, t_min, t_max).is_some()
+    }
+
+    /// Part of `t_min..t_max` in which `ray` is inside the box, if any.
+    pub fn range(&self, ray: &Ray, mut t_min: f64, mut t_max: f64) -> Option<(f64, f64)> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        for axis in 0..3 {
+            let inverse = 1.0 / direction[axis];
+            let mut t0 = (self.minimum[axis] - origin[axis]) * inverse;
+            let mut t1 = (self.maximum[axis] - origin[axis]) * inverse;
+            if inverse < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            // a NaN from a ray parallel to a face on it leaves the bounds as they are
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+            if t_max < t_min {
+                return None;
+            }
+        }
+        Some((t_min, t_max))
+    }
+
+    /// Smallest box containing both boxes.
+    pub fn surrounding(&self, other: &AABB) -> AABB {
+        AABB::new(
+            self.minimum.min(&other.minimum),
+            self.maximum.max(&other.maximum),
+        )
+    }
+
+    /// The 8 corners of the box.
+    pub fn corners(&self) -> [Point3; 8] {
+        let (a, b) = (self.minimum, self.maximum);
+        [0, 1, 2, 3, 4, 5, 6, 7].map(|i| {
+            Point3::new(
+                if i & 1 == 0 { a.x() } else { b.x() },
+                if i & 2 == 0 { a.y() } else { b.y() },
+                if i & 4 == 0 { a.z() } else { b.z() },
+            )
+        })
+    }
+}
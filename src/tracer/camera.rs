@@ -0,0 +1,70 @@
+use super::{
+    ray::Ray,
+    vec3::{cross, random_in_unit_disk, unit_vector, Point3, Vec3},
+};
+use rand::Rng;
+
+/// Perspective camera with a thin lens, which blurs what is away from the focus distance.
+pub struct Camera {
+    origin: Point3,
+    lower_left_corner: Point3,
+    horizontal: Vec3,
+    vertical: Vec3,
+    u: Vec3,
+    v: Vec3,
+    lens_radius: f64,
+    time0: f64,
+    time1: f64,
+}
+
+impl Camera {
+    /// A camera at `lookfrom` facing `lookat` with `vup` pointing up in the image. `vfov` is
+    /// the vertical field of view in degrees, and the shutter is open from `time0` to `time1`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        lookfrom: Point3,
+        lookat: Point3,
+        vup: Vec3,
+        vfov: f64,
+        aspect_ratio: f64,
+        aperture: f64,
+        focus_dist: f64,
+        time0: f64,
+        time1: f64,
+    ) -> Self {
+        let h = (vfov.to_radians() / 2.0).tan();
+        let viewport_height = 2.0 * h;
+        let viewport_width = aspect_ratio * viewport_height;
+
+        let w = unit_vector(&(lookfrom - lookat));
+        let u = unit_vector(&cross(&vup, &w));
+        let v = cross(&w, &u);
+
+        let horizontal = u * (focus_dist * viewport_width);
+        let vertical = v * (focus_dist * viewport_height);
+        Camera {
+            origin: lookfrom,
+            lower_left_corner: lookfrom - horizontal / 2.0 - vertical / 2.0 - w * focus_dist,
+            horizontal,
+            vertical,
+            u,
+            v,
+            lens_radius: aperture / 2.0,
+            time0,
+            time1,
+        }
+    }
+
+    /// Ray through the point `s` of the way across and `t` of the way up the image, from a
+    /// random point of the lens at a random time while the shutter is open.
+    pub fn get_ray(&self, s: f64, t: f64) -> Ray {
+        let rd = random_in_unit_disk() * self.lens_radius;
+        let offset = self.u * rd.x() + self.v * rd.y();
+        let time = self.time0 + (self.time1 - self.time0) * rand::thread_rng().gen_range(0.0..1.0);
+        Ray::new(
+            self.origin + offset,
+            self.lower_left_corner + self.horizontal * s + self.vertical * t - self.origin - offset,
+            time,
+        )
+    }
+}
@@ -0,0 +1,188 @@
+mod blob;
+mod bvh;
+mod cone;
+mod constant_medium;
+mod csg;
+mod cuboid;
+mod cylinder;
+mod disk;
+mod instance;
+mod isosurface;
+mod moving_translation;
+mod quad;
+mod rect;
+mod sphere;
+mod torus;
+mod transform;
+mod triangle;
+
+pub use blob::{Blob, BlobComponent};
+pub use bvh::BvhNode;
+pub use cone::Cone;
+pub use constant_medium::ConstantMedium;
+pub use csg::Csg;
+pub use cuboid::Cuboid;
+pub use cylinder::Cylinder;
+pub use disk::Disk;
+pub use instance::Instance;
+pub use isosurface::{Field, Isosurface};
+pub use moving_translation::MovingTranslation;
+pub use quad::Quad;
+pub use rect::{XYRect, XZRect, YZRect};
+pub use sphere::Sphere;
+pub use torus::Torus;
+pub use transform::Transform;
+pub use triangle::Triangle;
+
+use super::{
+    aabb::AABB,
+    material::MaterialEnum,
+    ray::Ray,
+    vec3::{cross, dot, unit_vector, Point3, Vec3},
+};
+
+/// Where a ray hits a surface.
+pub struct HitRecord<'a> {
+    pub p: Point3,
+    /// Unit normal on the side the ray comes from.
+    pub normal: Vec3,
+    pub t: f64,
+    pub u: f64,
+    pub v: f64,
+    /// Whether the ray enters the object here rather than leaving it.
+    pub front_face: bool,
+    pub material: &'a MaterialEnum,
+}
+
+impl<'a> HitRecord<'a> {
+    /// The hit of `ray` at `t` on a surface whose unit normal pointing out of the object is
+    /// `outward_normal`.
+    pub fn new(
+        ray: &Ray,
+        t: f64,
+        outward_normal: Vec3,
+        (u, v): (f64, f64),
+        material: &'a MaterialEnum,
+    ) -> Self {
+        let front_face = dot(&ray.direction(), &outward_normal) < 0.0;
+        HitRecord {
+            p: ray.at(t),
+            normal: if front_face {
+                outward_normal
+            } else {
+                -outward_normal
+            },
+            t,
+            u,
+            v,
+            front_face,
+            material,
+        }
+    }
+}
+
+pub trait Hittable {
+    /// The nearest hit of `ray` between `t_min` and `t_max`, exclusive.
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>>;
+
+    /// Box holding the object over the times from `t0` to `t1`, or `None` if it is unbounded.
+    fn bounding_box(&self, t0: f64, t1: f64) -> Option<AABB>;
+}
+
+pub enum HittableEnum {
+    Sphere(Sphere),
+    Cuboid(Cuboid),
+    XYRect(XYRect),
+    XZRect(XZRect),
+    YZRect(YZRect),
+    BvhNode(Box<BvhNode>),
+    MovingTranslation(Box<MovingTranslation>),
+    Quad(Quad),
+    Triangle(Triangle),
+    Cylinder(Cylinder),
+    Cone(Cone),
+    Disk(Disk),
+    Torus(Torus),
+    Csg(Box<Csg>),
+    Transform(Box<Transform>),
+    ConstantMedium(Box<ConstantMedium>),
+    Isosurface(Box<Isosurface>),
+    Blob(Box<Blob>),
+    Instance(Box<Instance>),
+}
+
+impl Hittable for HittableEnum {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        match self {
+            HittableEnum::Sphere(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Cuboid(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::XYRect(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::XZRect(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::YZRect(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::BvhNode(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::MovingTranslation(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Quad(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Triangle(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Cylinder(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Cone(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Disk(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Torus(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Csg(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Transform(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::ConstantMedium(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Isosurface(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Blob(h) => h.hit(ray, t_min, t_max),
+            HittableEnum::Instance(h) => h.hit(ray, t_min, t_max),
+        }
+    }
+
+    fn bounding_box(&self, t0: f64, t1: f64) -> Option<AABB> {
+        match self {
+            HittableEnum::Sphere(h) => h.bounding_box(t0, t1),
+            HittableEnum::Cuboid(h) => h.bounding_box(t0, t1),
+            HittableEnum::XYRect(h) => h.bounding_box(t0, t1),
+            HittableEnum::XZRect(h) => h.bounding_box(t0, t1),
+            HittableEnum::YZRect(h) => h.bounding_box(t0, t1),
+            HittableEnum::BvhNode(h) => h.bounding_box(t0, t1),
+            HittableEnum::MovingTranslation(h) => h.bounding_box(t0, t1),
+            HittableEnum::Quad(h) => h.bounding_box(t0, t1),
+            HittableEnum::Triangle(h) => h.bounding_box(t0, t1),
+            HittableEnum::Cylinder(h) => h.bounding_box(t0, t1),
+            HittableEnum::Cone(h) => h.bounding_box(t0, t1),
+            HittableEnum::Disk(h) => h.bounding_box(t0, t1),
+            HittableEnum::Torus(h) => h.bounding_box(t0, t1),
+            HittableEnum::Csg(h) => h.bounding_box(t0, t1),
+            HittableEnum::Transform(h) => h.bounding_box(t0, t1),
+            HittableEnum::ConstantMedium(h) => h.bounding_box(t0, t1),
+            HittableEnum::Isosurface(h) => h.bounding_box(t0, t1),
+            HittableEnum::Blob(h) => h.bounding_box(t0, t1),
+            HittableEnum::Instance(h) => h.bounding_box(t0, t1),
+        }
+    }
+}
+
+/// Two unit vectors perpendicular to the unit vector `w` and to each other, so that with `w`
+/// they are right-handed.
+fn perpendicular_basis(w: &Vec3) -> (Vec3, Vec3) {
+    let a = if w.x().abs() > 0.9 {
+        Vec3::new(0.0, 1.0, 0.0)
+    } else {
+        Vec3::new(1.0, 0.0, 0.0)
+    };
+    let v = unit_vector(&cross(w, &a));
+    let u = cross(&v, w);
+    (u, v)
+}
+
+/// Half the size in each axis of the box around a circle of `radius` facing the unit vector
+/// `axis`.
+fn circle_extent(axis: &Vec3, radius: f64) -> Vec3 {
+    let extent = |c: f64| radius * (1.0 - c * c).max(0.0).sqrt();
+    Vec3::new(extent(axis.x()), extent(axis.y()), extent(axis.z()))
+}
+
+/// Box with some thickness in every axis, for flat objects.
+fn padded(minimum: Point3, maximum: Point3) -> AABB {
+    let pad = Vec3::new(1e-4, 1e-4, 1e-4);
+    AABB::new(minimum - pad, maximum + pad)
+}
@@ -0,0 +1,169 @@
+use super::{HitRecord, Hittable, AABB};
+use crate::tracer::{
+    material::MaterialEnum,
+    ray::Ray,
+    roots::{quadratic, roots_between},
+    vec3::{dot, unit_vector, Point3, Vec3},
+};
+
+/// Sphere of influence of a `Blob`, with a field of `strength` × (1 - d² / `radius`²)² at the
+/// distance d from `center` and 0 beyond `radius`.
+pub struct BlobComponent {
+    center: Point3,
+    radius: f64,
+    strength: f64,
+}
+
+impl BlobComponent {
+    pub fn new(center: &Point3, radius: f64, strength: f64) -> Self {
+        BlobComponent {
+            center: *center,
+            radius,
+            strength,
+        }
+    }
+
+    /// Part of `ray` inside the sphere of influence.
+    fn range(&self, ray: &Ray) -> Option<(f64, f64)> {
+        let oc = ray.origin() - self.center;
+        match quadratic(
+            ray.direction().length_squared(),
+            2.0 * dot(&oc, &ray.direction()),
+            oc.length_squared() - self.radius * self.radius,
+        )[..]
+        {
+            [t0, t1] => Some((t0, t1)),
+            _ => None,
+        }
+    }
+
+    /// The field along `ray` as the coefficients of a quartic in t, from the constant term up.
+    fn field_along(&self, ray: &Ray) -> [f64; 5] {
+        let oc = ray.origin() - self.center;
+        let r2 = self.radius * self.radius;
+        // 1 - d² / radius², a quadratic in t
+        let q = [
+            1.0 - oc.length_squared() / r2,
+            -2.0 * dot(&oc, &ray.direction()) / r2,
+            -ray.direction().length_squared() / r2,
+        ];
+        let s = self.strength;
+        [
+            s * q[0] * q[0],
+            s * 2.0 * q[0] * q[1],
+            s * (q[1] * q[1] + 2.0 * q[0] * q[2]),
+            s * 2.0 * q[1] * q[2],
+            s * q[2] * q[2],
+        ]
+    }
+
+    /// Gradient of the field at `p`.
+    fn gradient(&self, p: &Point3) -> Vec3 {
+        let offset = *p - self.center;
+        let r2 = self.radius * self.radius;
+        let falloff = 1.0 - offset.length_squared() / r2;
+        if falloff <= 0.0 {
+            return Vec3::zero();
+        }
+        offset * (-4.0 * self.strength * falloff / r2)
+    }
+}
+
+/// Metaballs: the surface where the sum of the fields of `components` is `threshold`, with the
+/// inside where it is greater.
+pub struct Blob {
+    components: Vec<BlobComponent>,
+    threshold: f64,
+    material: MaterialEnum,
+}
+
+impl Blob {
+    pub fn new(components: Vec<BlobComponent>, threshold: f64, material: MaterialEnum) -> Self {
+        Blob {
+            components,
+            threshold,
+            material,
+        }
+    }
+}
+
+impl Hittable for Blob {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // between the points where the ray enters or leaves a sphere of influence, the same
+        // components act and the field is a single quartic
+        let ranges: Vec<_> = self
+            .components
+            .iter()
+            .map(|component| {
+                component
+                    .range(ray)
+                    .map(|(t0, t1)| (t0.max(t_min), t1.min(t_max)))
+                    .filter(|(t0, t1)| t0 < t1)
+            })
+            .collect();
+        let mut ends: Vec<f64> = ranges
+            .iter()
+            .flatten()
+            .flat_map(|(t0, t1)| [*t0, *t1])
+            .collect();
+        ends.sort_by(f64::total_cmp);
+        ends.dedup();
+        let &start = ends.first()?;
+        // solving from where the ray first meets a component, along a unit direction, keeps
+        // the coefficients of the quartics in proportion to the size of the components
+        let length = ray.direction().length();
+        let local = Ray::new(ray.at(start), ray.direction() / length, ray.time());
+        let to_local = |t: f64| (t - start) * length;
+        for pair in ends.windows(2) {
+            let (lo, hi) = (pair[0], pair[1]);
+            let mid = 0.5 * (lo + hi);
+            let mut field = [-self.threshold, 0.0, 0.0, 0.0, 0.0];
+            for (component, range) in self.components.iter().zip(ranges.iter()) {
+                if range.is_some_and(|(t0, t1)| t0 <= mid && mid <= t1) {
+                    for (sum, c) in field.iter_mut().zip(component.field_along(&local)) {
+                        *sum += c;
+                    }
+                }
+            }
+            let Some(t) = roots_between(&field, to_local(lo), to_local(hi))
+                .into_iter()
+                .map(|s| start + s / length)
+                .find(|t| t_min < *t && *t < t_max)
+            else {
+                continue;
+            };
+            let p = ray.at(t);
+            let gradient = self
+                .components
+                .iter()
+                .fold(Vec3::zero(), |sum, component| sum + component.gradient(&p));
+            // the field grows inward
+            let outward_normal = if gradient.near_zero() {
+                unit_vector(&-ray.direction())
+            } else {
+                unit_vector(&-gradient)
+            };
+            return Some(HitRecord::new(
+                ray,
+                t,
+                outward_normal,
+                (0.0, 0.0),
+                &self.material,
+            ));
+        }
+        None
+    }
+
+    fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+        // only components with a positive strength can raise the field to the threshold
+        self.components
+            .iter()
+            .filter(|component| component.strength > 0.0)
+            .map(|component| {
+                let r = Vec3::new(component.radius, component.radius, component.radius);
+                AABB::new(component.center - r, component.center + r)
+            })
+            .reduce(|a, b| a.surrounding(&b))
+            .or(Some(AABB::new(Vec3::zero(), Vec3::zero())))
+    }
+}
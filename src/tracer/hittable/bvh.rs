@@ -0,0 +1,90 @@
+use super::{HitRecord, Hittable, HittableEnum, AABB};
+use crate::tracer::ray::Ray;
+
+/// Bounding volume hierarchy, which skips the objects whose boxes a ray misses.
+pub struct BvhNode {
+    /// At most 2 children, which are nodes themselves unless there were only 2 objects.
+    children: Vec<HittableEnum>,
+    /// `None` if the node is empty or holds an unbounded object.
+    bbox: Option<AABB>,
+}
+
+impl BvhNode {
+    /// Moves all of `objects` into a hierarchy for rays cast from `time0` to `time1`.
+    pub fn new(objects: &mut Vec<HittableEnum>, time0: f64, time1: f64) -> Self {
+        Self::build(std::mem::take(objects), time0, time1)
+    }
+
+    fn build(mut objects: Vec<HittableEnum>, time0: f64, time1: f64) -> Self {
+        if objects.len() > 2 {
+            // split along the axis in which the centers of the objects are spread the most
+            let centers: Vec<_> = objects
+                .iter()
+                .map(|object| {
+                    object
+                        .bounding_box(time0, time1)
+                        .map(|bbox| (bbox.min() + bbox.max()) / 2.0)
+                })
+                .collect();
+            let bounded = centers.iter().flatten();
+            let low = bounded.clone().fold(None, |low: Option<_>, c| {
+                Some(low.map_or(*c, |low| c.min(&low)))
+            });
+            let high = bounded.fold(None, |high: Option<_>, c| {
+                Some(high.map_or(*c, |high| c.max(&high)))
+            });
+            let axis = match (low, high) {
+                (Some(low), Some(high)) => {
+                    let spread = high - low;
+                    (0..3)
+                        .max_by(|a, b| spread[*a].total_cmp(&spread[*b]))
+                        .unwrap_or(0)
+                }
+                _ => 0,
+            };
+            let mut keyed: Vec<_> = objects
+                .into_iter()
+                .zip(centers)
+                .map(|(object, center)| (center.map_or(0.0, |c| c[axis]), object))
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            objects = keyed.into_iter().map(|(_, object)| object).collect();
+            let right = objects.split_off(objects.len() / 2);
+            objects = vec![
+                HittableEnum::BvhNode(Box::new(Self::build(objects, time0, time1))),
+                HittableEnum::BvhNode(Box::new(Self::build(right, time0, time1))),
+            ];
+        }
+        let bbox = objects
+            .iter()
+            .map(|object| object.bounding_box(time0, time1))
+            .reduce(|a, b| Some(a?.surrounding(&b?)))
+            .flatten();
+        BvhNode {
+            children: objects,
+            bbox,
+        }
+    }
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, ray: &Ray, t_min: f64, mut t_max: f64) -> Option<HitRecord<'_>> {
+        if let Some(bbox) = &self.bbox {
+            if !bbox.hit(ray, t_min, t_max) {
+                return None;
+            }
+        }
+        let mut closest = None;
+        for child in self.children.iter() {
+            if let Some(rec) = child.hit(ray, t_min, t_max) {
+                t_max = rec.t;
+                closest = Some(rec);
+            }
+        }
+        closest
+    }
+
+    fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+        self.bbox
+    }
+}
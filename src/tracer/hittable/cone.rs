@@ -0,0 +1,141 @@
+use super::{circle_extent, perpendicular_basis, HitRecord, Hittable, AABB};
+use crate::tracer::{
+    material::MaterialEnum,
+    ray::Ray,
+    roots::quadratic,
+    vec3::{dot, unit_vector, Point3, Vec3},
+};
+use std::f64::consts::PI;
+
+/// Cone with its tip cut off at `cap`, or a full cone if one of the radii is 0. The ends
+/// with a radius are closed with flat disks.
+pub struct Cone(Frustum);
+
+impl Cone {
+    pub fn new(
+        base: &Point3,
+        base_radius: f64,
+        cap: &Point3,
+        cap_radius: f64,
+        material: MaterialEnum,
+    ) -> Self {
+        Cone(Frustum::new(
+            base,
+            base_radius,
+            cap,
+            cap_radius,
+            true,
+            material,
+        ))
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        self.0.hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+        Some(self.0.bounding_box())
+    }
+}
+
+/// Surface around the segment from `base` to `base + axis * height` whose radius changes
+/// linearly from `base_radius` to `cap_radius`, shared by cones and cylinders.
+pub(super) struct Frustum {
+    base: Point3,
+    axis: Vec3,
+    height: f64,
+    base_radius: f64,
+    cap_radius: f64,
+    /// Whether the ends are closed.
+    closed: bool,
+    material: MaterialEnum,
+}
+
+impl Frustum {
+    pub(super) fn new(
+        base: &Point3,
+        base_radius: f64,
+        cap: &Point3,
+        cap_radius: f64,
+        closed: bool,
+        material: MaterialEnum,
+    ) -> Self {
+        let along = *cap - *base;
+        Frustum {
+            base: *base,
+            axis: unit_vector(&along),
+            height: along.length(),
+            base_radius,
+            cap_radius,
+            closed,
+            material,
+        }
+    }
+
+    /// Texture coordinates of the point `offset` from the base: the angle around the axis,
+    /// and `along` from 0 to 1.
+    fn uv(&self, offset: &Vec3, along: f64) -> (f64, f64) {
+        let (a, b) = perpendicular_basis(&self.axis);
+        let angle = dot(offset, &b).atan2(dot(offset, &a));
+        ((angle + PI) / (2.0 * PI), along)
+    }
+
+    pub(super) fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let (w, h) = (self.axis, self.height);
+        let (ra, slope) = (self.base_radius, (self.cap_radius - self.base_radius) / h);
+        let oc = ray.origin() - self.base;
+        let d = ray.direction();
+        let (dv, ov) = (dot(&d, &w), dot(&oc, &w));
+        // squared distance from the axis minus the squared radius at the same height, as a
+        // quadratic in t
+        let a = d.length_squared() - dv * dv - slope * slope * dv * dv;
+        let b = 2.0 * (dot(&oc, &d) - ov * dv - (ra + slope * ov) * slope * dv);
+        let c = oc.length_squared() - ov * ov - (ra + slope * ov).powi(2);
+
+        let mut best: Option<(f64, Vec3, (f64, f64))> = None;
+        let mut consider = |t: f64, normal: Vec3, uv: (f64, f64)| {
+            if t_min < t && t < t_max && best.is_none_or(|(best_t, _, _)| t < best_t) {
+                best = Some((t, normal, uv));
+            }
+        };
+        for t in quadratic(a, b, c) {
+            let s = ov + t * dv;
+            if !(0.0..=h).contains(&s) {
+                continue;
+            }
+            let p = oc + d * t;
+            let radial = p - w * s;
+            let gradient = radial - w * ((ra + slope * s) * slope);
+            // the tip of a cone has no normal
+            if gradient.near_zero() {
+                continue;
+            }
+            consider(t, unit_vector(&gradient), self.uv(&radial, s / h));
+        }
+        if self.closed {
+            for (s, radius, normal) in [(0.0, ra, -w), (h, self.cap_radius, w)] {
+                if radius <= 0.0 {
+                    continue;
+                }
+                let t = (s - ov) / dv;
+                let radial = oc + d * t - w * s;
+                let distance = radial.length();
+                if distance <= radius {
+                    consider(t, normal, self.uv(&radial, distance / radius));
+                }
+            }
+        }
+        let (t, outward_normal, uv) = best?;
+        Some(HitRecord::new(ray, t, outward_normal, uv, &self.material))
+    }
+
+    pub(super) fn bounding_box(&self) -> AABB {
+        let cap = self.base + self.axis * self.height;
+        let base_extent = circle_extent(&self.axis, self.base_radius);
+        let cap_extent = circle_extent(&self.axis, self.cap_radius);
+        AABB::new(self.base - base_extent, self.base + base_extent)
+            .surrounding(&AABB::new(cap - cap_extent, cap + cap_extent))
+    }
+}
@@ -0,0 +1,104 @@
+use super::{HitRecord, Hittable, HittableEnum, AABB};
+use crate::tracer::{
+    material::{Isotropic, MaterialEnum},
+    perlin::Perlin,
+    ray::Ray,
+    vec3::{Color, Vec3},
+};
+use rand::Rng;
+
+/// How far past a surface of the boundary the search for the next one starts.
+const STEP: f64 = 1e-7;
+
+/// Smoke or mist filling the closed object `boundary`, scattering light of `color`. A ray goes
+/// 1 / `density` on average before it scatters.
+pub struct ConstantMedium {
+    boundary: HittableEnum,
+    density: f64,
+    /// Scale of the Perlin noise that varies the density, if it varies.
+    noise: Option<f64>,
+    phase_function: MaterialEnum,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: HittableEnum, density: f64, color: Color) -> Self {
+        ConstantMedium {
+            boundary,
+            density,
+            noise: None,
+            phase_function: MaterialEnum::Isotropic(Isotropic::new(color)),
+        }
+    }
+
+    /// The medium with its density varying from 0 to twice `density` with Perlin noise of
+    /// `scale`, staying `density` on average.
+    pub fn with_noise(self, scale: f64) -> Self {
+        ConstantMedium {
+            noise: Some(scale),
+            ..self
+        }
+    }
+
+    /// Where `ray` scatters between `start` and `end`, which are inside the boundary.
+    fn scatter_between(&self, ray: &Ray, start: f64, end: f64) -> Option<f64> {
+        let mut rng = rand::thread_rng();
+        let speed = ray.direction().length();
+        let Some(scale) = self.noise else {
+            let t = start - rng.gen_range(f64::EPSILON..1.0).ln() / (self.density * speed);
+            return (t < end).then_some(t);
+        };
+        // delta tracking: of the scatterings in a medium of the highest density, a point keeps
+        // the share its own density is of that
+        let highest = 2.0 * self.density;
+        let perlin = Perlin::shared();
+        let mut t = start;
+        loop {
+            t -= rng.gen_range(f64::EPSILON..1.0).ln() / (highest * speed);
+            if t >= end {
+                return None;
+            }
+            let share = 0.5 * (1.0 + perlin.noise(&(ray.at(t) * scale)));
+            if rng.gen::<f64>() < share {
+                return Some(t);
+            }
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // the boundary need not be convex, so the ray can pass through the medium several
+        // times; the next surface being one the ray leaves through means it starts inside
+        let mut t = t_min;
+        while t < t_max {
+            let first = self.boundary.hit(ray, t, f64::INFINITY)?;
+            let (start, end) = if first.front_face {
+                let exit = self.boundary.hit(ray, first.t + STEP, f64::INFINITY)?;
+                (first.t, exit.t)
+            } else {
+                (t, first.t)
+            };
+            if start >= t_max {
+                return None;
+            }
+            if let Some(t) = self.scatter_between(ray, start, end.min(t_max)) {
+                return Some(HitRecord {
+                    p: ray.at(t),
+                    // arbitrary, since the phase function does not use them
+                    normal: Vec3::new(1.0, 0.0, 0.0),
+                    t,
+                    u: 0.0,
+                    v: 0.0,
+                    front_face: true,
+                    material: &self.phase_function,
+                });
+            }
+            t = end + STEP;
+        }
+        None
+    }
+
+    fn bounding_box(&self, t0: f64, t1: f64) -> Option<AABB> {
+        self.boundary.bounding_box(t0, t1)
+    }
+}
@@ -0,0 +1,109 @@
+use super::{HitRecord, Hittable, HittableEnum, AABB};
+use crate::tracer::ray::Ray;
+
+/// How far past a hit the search for the next one starts, so that it does not find the same
+/// surface again through rounding.
+const STEP: f64 = 1e-7;
+
+enum Operation {
+    Difference,
+    Intersection,
+    Merge,
+}
+
+impl Operation {
+    /// Whether a point inside `a` or not and inside `b` or not is inside the result.
+    fn inside(&self, a: bool, b: bool) -> bool {
+        match self {
+            Operation::Difference => a && !b,
+            Operation::Intersection => a && b,
+            Operation::Merge => a || b,
+        }
+    }
+}
+
+/// Solid combining `a` and `b`. Both must be closed, so that whether a ray enters or leaves
+/// them tells their inside apart.
+pub struct Csg {
+    a: HittableEnum,
+    b: HittableEnum,
+    operation: Operation,
+}
+
+impl Csg {
+    /// The part of `a` outside `b`.
+    pub fn difference(a: HittableEnum, b: HittableEnum) -> Self {
+        Csg {
+            a,
+            b,
+            operation: Operation::Difference,
+        }
+    }
+
+    /// The part of `a` inside `b`.
+    pub fn intersection(a: HittableEnum, b: HittableEnum) -> Self {
+        Csg {
+            a,
+            b,
+            operation: Operation::Intersection,
+        }
+    }
+
+    /// Both `a` and `b`, without the surfaces of either inside the other.
+    pub fn merge(a: HittableEnum, b: HittableEnum) -> Self {
+        Csg {
+            a,
+            b,
+            operation: Operation::Merge,
+        }
+    }
+}
+
+impl Hittable for Csg {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // the ray starts inside an operand if the next surface of it is one it leaves through
+        let mut next_a = self.a.hit(ray, t_min, f64::INFINITY);
+        let mut next_b = self.b.hit(ray, t_min, f64::INFINITY);
+        let mut in_a = next_a.as_ref().is_some_and(|rec| !rec.front_face);
+        let mut in_b = next_b.as_ref().is_some_and(|rec| !rec.front_face);
+        let mut inside = self.operation.inside(in_a, in_b);
+        loop {
+            let a_first = match (&next_a, &next_b) {
+                (Some(a), Some(b)) => a.t <= b.t,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => return None,
+            };
+            let mut rec = if a_first {
+                let rec = next_a.take()?;
+                in_a = rec.front_face;
+                next_a = self.a.hit(ray, rec.t + STEP, f64::INFINITY);
+                rec
+            } else {
+                let rec = next_b.take()?;
+                in_b = rec.front_face;
+                next_b = self.b.hit(ray, rec.t + STEP, f64::INFINITY);
+                rec
+            };
+            if rec.t >= t_max {
+                return None;
+            }
+            let now_inside = self.operation.inside(in_a, in_b);
+            if now_inside != inside {
+                // the normal already faces the ray; a ray entering the solid can leave `b`
+                rec.front_face = now_inside;
+                return Some(rec);
+            }
+            inside = now_inside;
+        }
+    }
+
+    fn bounding_box(&self, t0: f64, t1: f64) -> Option<AABB> {
+        let a = self.a.bounding_box(t0, t1);
+        let b = self.b.bounding_box(t0, t1);
+        match self.operation {
+            Operation::Difference | Operation::Intersection => a,
+            Operation::Merge => Some(a?.surrounding(&b?)),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use super::{HitRecord, Hittable, AABB};
+use crate::tracer::{
+    material::MaterialEnum,
+    ray::Ray,
+    vec3::{Point3, Vec3},
+};
+
+/// Box with faces along the axes between two opposite corners.
+pub struct Cuboid {
+    minimum: Point3,
+    maximum: Point3,
+    material: MaterialEnum,
+}
+
+impl Cuboid {
+    pub fn new(p0: &Point3, p1: &Point3, material: MaterialEnum) -> Self {
+        Cuboid {
+            minimum: p0.min(p1),
+            maximum: p0.max(p1),
+            material,
+        }
+    }
+
+    /// Outward normal of the face across `axis` that `ray` crosses at `t`, with the face's
+    /// own coordinates running along the next two axes.
+    fn face(&self, ray: &Ray, t: f64, axis: usize, entering: bool) -> (Vec3, (f64, f64)) {
+        let toward_max = (ray.direction()[axis] > 0.0) != entering;
+        let mut e = [0.0; 3];
+        e[axis] = if toward_max { 1.0 } else { -1.0 };
+        let p = ray.at(t);
+        let local = |i: usize| {
+            let i = (axis + i) % 3;
+            (p[i] - self.minimum[i]) / (self.maximum[i] - self.minimum[i])
+        };
+        (Vec3::new(e[0], e[1], e[2]), (local(1), local(2)))
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        let (mut t_near, mut t_far) = (f64::NEG_INFINITY, f64::INFINITY);
+        let (mut near_axis, mut far_axis) = (0, 0);
+        for axis in 0..3 {
+            let inverse = 1.0 / direction[axis];
+            let mut t0 = (self.minimum[axis] - origin[axis]) * inverse;
+            let mut t1 = (self.maximum[axis] - origin[axis]) * inverse;
+            if inverse < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            if t0 > t_near {
+                (t_near, near_axis) = (t0, axis);
+            }
+            if t1 < t_far {
+                (t_far, far_axis) = (t1, axis);
+            }
+        }
+        if t_near > t_far {
+            return None;
+        }
+        let (t, axis, entering) = if t_min < t_near && t_near < t_max {
+            (t_near, near_axis, true)
+        } else if t_min < t_far && t_far < t_max {
+            (t_far, far_axis, false)
+        } else {
+            return None;
+        };
+        let (outward_normal, uv) = self.face(ray, t, axis, entering);
+        Some(HitRecord::new(ray, t, outward_normal, uv, &self.material))
+    }
+
+    fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+        Some(AABB::new(self.minimum, self.maximum))
+    }
+}
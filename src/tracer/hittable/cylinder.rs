@@ -0,0 +1,27 @@
+use super::{cone::Frustum, HitRecord, Hittable, AABB};
+use crate::tracer::{material::MaterialEnum, ray::Ray, vec3::Point3};
+
+/// Cylinder from `base` to `cap`, closed at both ends with flat disks unless `open`.
+pub struct Cylinder(Frustum);
+
+impl Cylinder {
+    pub fn new(
+        base: &Point3,
+        cap: &Point3,
+        radius: f64,
+        open: bool,
+        material: MaterialEnum,
+    ) -> Self {
+        Cylinder(Frustum::new(base, radius, cap, radius, !open, material))
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        self.0.hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+        Some(self.0.bounding_box())
+    }
+}
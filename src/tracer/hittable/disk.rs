@@ -0,0 +1,51 @@
+use super::{circle_extent, padded, perpendicular_basis, HitRecord, Hittable, AABB};
+use crate::tracer::{
+    material::MaterialEnum,
+    ray::Ray,
+    vec3::{dot, unit_vector, Point3, Vec3},
+};
+use std::f64::consts::PI;
+
+/// Flat circle facing `normal`.
+pub struct Disk {
+    center: Point3,
+    normal: Vec3,
+    radius: f64,
+    material: MaterialEnum,
+}
+
+impl Disk {
+    pub fn new(center: &Point3, normal: &Vec3, radius: f64, material: MaterialEnum) -> Self {
+        Disk {
+            center: *center,
+            normal: unit_vector(normal),
+            radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let t =
+            dot(&(self.center - ray.origin()), &self.normal) / dot(&ray.direction(), &self.normal);
+        if !(t_min < t && t < t_max) {
+            return None;
+        }
+        let offset = ray.at(t) - self.center;
+        let distance = offset.length();
+        if distance > self.radius {
+            return None;
+        }
+        // angle around the center, and distance out from it
+        let (a, b) = perpendicular_basis(&self.normal);
+        let angle = dot(&offset, &b).atan2(dot(&offset, &a));
+        let uv = ((angle + PI) / (2.0 * PI), distance / self.radius);
+        Some(HitRecord::new(ray, t, self.normal, uv, &self.material))
+    }
+
+    fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+        let extent = circle_extent(&self.normal, self.radius);
+        Some(padded(self.center - extent, self.center + extent))
+    }
+}
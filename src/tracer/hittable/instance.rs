@@ -0,0 +1,33 @@
+use super::{HitRecord, Hittable, HittableEnum, AABB};
+use crate::tracer::{material::MaterialEnum, ray::Ray};
+use std::sync::Arc;
+
+/// Another use of the objects in `prototype`, which it shares with every other instance of
+/// them. With `material`, it is drawn in that material instead of their own.
+pub struct Instance {
+    prototype: Arc<HittableEnum>,
+    material: Option<MaterialEnum>,
+}
+
+impl Instance {
+    pub fn new(prototype: Arc<HittableEnum>, material: Option<MaterialEnum>) -> Self {
+        Instance {
+            prototype,
+            material,
+        }
+    }
+}
+
+impl Hittable for Instance {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let mut rec = self.prototype.hit(ray, t_min, t_max)?;
+        if let Some(material) = &self.material {
+            rec.material = material;
+        }
+        Some(rec)
+    }
+
+    fn bounding_box(&self, t0: f64, t1: f64) -> Option<AABB> {
+        self.prototype.bounding_box(t0, t1)
+    }
+}
@@ -0,0 +1,115 @@
+use super::{HitRecord, Hittable, AABB};
+use crate::tracer::{
+    material::MaterialEnum,
+    ray::Ray,
+    vec3::{unit_vector, Point3, Vec3},
+};
+use std::sync::Arc;
+
+/// Function of a point whose level set an `Isosurface` draws.
+pub type Field = Arc<dyn Fn(&Vec3) -> f64 + Send + Sync>;
+
+/// Bisections of a step in which the field crosses the threshold.
+const BISECTIONS: usize = 60;
+
+/// Surface where `field` equals `threshold` within the box from `minimum` to `maximum`, with
+/// the inside where `field` is smaller.
+pub struct Isosurface {
+    field: Field,
+    bounds: AABB,
+    threshold: f64,
+    /// Limit of how fast `field` changes with distance, so that a point where it is `d` from
+    /// the threshold is at least `d / max_gradient` from the surface.
+    max_gradient: f64,
+    /// Shortest step of the march, and the spacing of the differences that make the normal.
+    epsilon: f64,
+    material: MaterialEnum,
+}
+
+impl Isosurface {
+    pub fn new(
+        field: Field,
+        minimum: &Point3,
+        maximum: &Point3,
+        threshold: f64,
+        max_gradient: f64,
+        material: MaterialEnum,
+    ) -> Self {
+        Isosurface {
+            field,
+            bounds: AABB::new(*minimum, *maximum),
+            threshold,
+            max_gradient,
+            epsilon: 1e-4 * (*maximum - *minimum).length(),
+            material,
+        }
+    }
+
+    fn value(&self, p: &Point3) -> f64 {
+        (self.field)(p) - self.threshold
+    }
+
+    fn gradient(&self, p: &Point3) -> Vec3 {
+        let h = self.epsilon;
+        let difference = |offset: Vec3| self.value(&(*p + offset)) - self.value(&(*p - offset));
+        Vec3::new(
+            difference(Vec3::new(h, 0.0, 0.0)),
+            difference(Vec3::new(0.0, h, 0.0)),
+            difference(Vec3::new(0.0, 0.0, h)),
+        )
+    }
+}
+
+impl Hittable for Isosurface {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let (start, end) = self.bounds.range(ray, t_min, t_max)?;
+        let speed = ray.direction().length();
+        let value_at = |t: f64| self.value(&ray.at(t));
+        // sphere tracing: no step goes past the surface while the field changes no faster
+        // than `max_gradient`
+        let (mut t, mut value) = (start, value_at(start));
+        let (mut lo, mut hi) = loop {
+            if t >= end {
+                return None;
+            }
+            let step = (value.abs() / self.max_gradient).max(self.epsilon) / speed;
+            let next = (t + step).min(end);
+            let next_value = value_at(next);
+            if (next_value < 0.0) != (value < 0.0) {
+                break (t, next);
+            }
+            (t, value) = (next, next_value);
+        };
+        let inside_first = value < 0.0;
+        for _ in 0..BISECTIONS {
+            let mid = 0.5 * (lo + hi);
+            if (value_at(mid) < 0.0) == inside_first {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let t = hi;
+        if !(t_min < t && t < t_max) {
+            return None;
+        }
+        let gradient = self.gradient(&ray.at(t));
+        // a flat field has no normal; face the ray
+        let outward_normal = if gradient.near_zero() {
+            unit_vector(&-ray.direction())
+        } else {
+            unit_vector(&gradient)
+        };
+        Some(HitRecord::new(
+            ray,
+            t,
+            outward_normal,
+            (0.0, 0.0),
+            &self.material,
+        ))
+    }
+
+    fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+        Some(self.bounds)
+    }
+}
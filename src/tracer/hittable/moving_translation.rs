@@ -0,0 +1,50 @@
+use super::{HitRecord, Hittable, HittableEnum, AABB};
+use crate::tracer::{ray::Ray, vec3::Vec3};
+
+/// `obj` moving in a straight line from `offset0` at `time0` to `offset1` at `time1`.
+pub struct MovingTranslation {
+    obj: HittableEnum,
+    offset0: Vec3,
+    offset1: Vec3,
+    time0: f64,
+    time1: f64,
+}
+
+impl MovingTranslation {
+    pub fn new(obj: HittableEnum, offset0: Vec3, offset1: Vec3, time0: f64, time1: f64) -> Self {
+        MovingTranslation {
+            obj,
+            offset0,
+            offset1,
+            time0,
+            time1,
+        }
+    }
+
+    fn offset(&self, time: f64) -> Vec3 {
+        if self.time1 == self.time0 {
+            return self.offset0;
+        }
+        let progress = (time - self.time0) / (self.time1 - self.time0);
+        self.offset0 + (self.offset1 - self.offset0) * progress
+    }
+}
+
+impl Hittable for MovingTranslation {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let offset = self.offset(ray.time());
+        let moved = Ray::new(ray.origin() - offset, ray.direction(), ray.time());
+        let mut rec = self.obj.hit(&moved, t_min, t_max)?;
+        rec.p += offset;
+        Some(rec)
+    }
+
+    fn bounding_box(&self, t0: f64, t1: f64) -> Option<AABB> {
+        let bbox = self.obj.bounding_box(t0, t1)?;
+        let (offset0, offset1) = (self.offset(t0), self.offset(t1));
+        Some(
+            AABB::new(bbox.min() + offset0, bbox.max() + offset0)
+                .surrounding(&AABB::new(bbox.min() + offset1, bbox.max() + offset1)),
+        )
+    }
+}
@@ -0,0 +1,66 @@
+use super::{padded, HitRecord, Hittable, AABB};
+use crate::tracer::{
+    material::MaterialEnum,
+    ray::Ray,
+    vec3::{cross, dot, unit_vector, Point3, Vec3},
+};
+
+/// Parallelogram with a corner at `q` and sides `u` and `v`, facing `u` × `v`.
+pub struct Quad {
+    q: Point3,
+    u: Vec3,
+    v: Vec3,
+    normal: Vec3,
+    /// Turns a point of the plane relative to `q` into its coordinates along `u` and `v`.
+    w: Vec3,
+    material: MaterialEnum,
+}
+
+impl Quad {
+    pub fn new(q: &Point3, u: &Vec3, v: &Vec3, material: MaterialEnum) -> Self {
+        let n = cross(u, v);
+        Quad {
+            q: *q,
+            u: *u,
+            v: *v,
+            normal: unit_vector(&n),
+            w: n / dot(&n, &n),
+            material,
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let denominator = dot(&self.normal, &ray.direction());
+        // parallel to the plane
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+        let t = dot(&self.normal, &(self.q - ray.origin())) / denominator;
+        if !(t_min < t && t < t_max) {
+            return None;
+        }
+        let planar = ray.at(t) - self.q;
+        let alpha = dot(&self.w, &cross(&planar, &self.v));
+        let beta = dot(&self.w, &cross(&self.u, &planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+        Some(HitRecord::new(
+            ray,
+            t,
+            self.normal,
+            (alpha, beta),
+            &self.material,
+        ))
+    }
+
+    fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+        let corners = [self.q + self.u, self.q + self.v, self.q + self.u + self.v];
+        let (minimum, maximum) = corners
+            .iter()
+            .fold((self.q, self.q), |(lo, hi), c| (lo.min(c), hi.max(c)));
+        Some(padded(minimum, maximum))
+    }
+}
@@ -0,0 +1,103 @@
+use super::{padded, HitRecord, Hittable, AABB};
+use crate::tracer::{material::MaterialEnum, ray::Ray, vec3::Point3};
+
+/// Rectangle in the plane where the axis `K` is `k`, spanning `a0..a1` and `b0..b1` along the
+/// other two axes in order. Its outward normal points along `K`.
+struct Rect<const K: usize> {
+    a0: f64,
+    a1: f64,
+    b0: f64,
+    b1: f64,
+    k: f64,
+    material: MaterialEnum,
+}
+
+impl<const K: usize> Rect<K> {
+    /// The other two axes, in the order x, y, z.
+    const AXES: (usize, usize) = match K {
+        0 => (1, 2),
+        1 => (0, 2),
+        _ => (0, 1),
+    };
+
+    fn point(&self, a: f64, b: f64, k: f64) -> Point3 {
+        let mut e = [0.0; 3];
+        e[Self::AXES.0] = a;
+        e[Self::AXES.1] = b;
+        e[K] = k;
+        Point3::new(e[0], e[1], e[2])
+    }
+
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let (origin, direction) = (ray.origin(), ray.direction());
+        let t = (self.k - origin[K]) / direction[K];
+        if !(t_min < t && t < t_max) {
+            return None;
+        }
+        let (ia, ib) = Self::AXES;
+        let a = origin[ia] + t * direction[ia];
+        let b = origin[ib] + t * direction[ib];
+        if a < self.a0 || a > self.a1 || b < self.b0 || b > self.b1 {
+            return None;
+        }
+        let uv = (
+            (a - self.a0) / (self.a1 - self.a0),
+            (b - self.b0) / (self.b1 - self.b0),
+        );
+        let outward_normal = self.point(0.0, 0.0, 1.0);
+        Some(HitRecord::new(ray, t, outward_normal, uv, &self.material))
+    }
+
+    fn bounding_box(&self) -> Option<AABB> {
+        Some(padded(
+            self.point(self.a0, self.b0, self.k),
+            self.point(self.a1, self.b1, self.k),
+        ))
+    }
+}
+
+macro_rules! rect {
+    ($(#[$doc:meta])* $name:ident, $axis:literal) => {
+        $(#[$doc])*
+        pub struct $name(Rect<$axis>);
+
+        impl $name {
+            pub fn new(a0: f64, a1: f64, b0: f64, b1: f64, k: f64, material: MaterialEnum) -> Self {
+                $name(Rect {
+                    a0,
+                    a1,
+                    b0,
+                    b1,
+                    k,
+                    material,
+                })
+            }
+        }
+
+        impl Hittable for $name {
+            fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+                self.0.hit(ray, t_min, t_max)
+            }
+
+            fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+                self.0.bounding_box()
+            }
+        }
+    };
+}
+
+rect!(
+    /// Rectangle from `x0..x1` and `y0..y1` at z = `k`, facing +z.
+    XYRect,
+    2
+);
+rect!(
+    /// Rectangle from `x0..x1` and `z0..z1` at y = `k`, facing +y.
+    XZRect,
+    1
+);
+rect!(
+    /// Rectangle from `y0..y1` and `z0..z1` at x = `k`, facing +x.
+    YZRect,
+    0
+);
@@ -0,0 +1,56 @@
+use super::{HitRecord, Hittable, AABB};
+use crate::tracer::{
+    material::MaterialEnum,
+    ray::Ray,
+    vec3::{dot, Point3, Vec3},
+};
+use std::f64::consts::PI;
+
+pub struct Sphere {
+    center: Point3,
+    radius: f64,
+    material: MaterialEnum,
+}
+
+impl Sphere {
+    pub fn new(center: &Point3, radius: f64, material: MaterialEnum) -> Self {
+        Sphere {
+            center: *center,
+            radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Sphere {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let oc = ray.origin() - self.center;
+        let a = ray.direction().length_squared();
+        let half_b = dot(&oc, &ray.direction());
+        let c = oc.length_squared() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let sqrtd = discriminant.sqrt();
+        let t = [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a]
+            .into_iter()
+            .find(|t| t_min < *t && *t < t_max)?;
+        let outward_normal = (ray.at(t) - self.center) / self.radius;
+        // longitude from -x around through z, and latitude from the bottom
+        let u = ((-outward_normal.z()).atan2(outward_normal.x()) + PI) / (2.0 * PI);
+        let v = (-outward_normal.y()).acos() / PI;
+        Some(HitRecord::new(
+            ray,
+            t,
+            outward_normal,
+            (u, v),
+            &self.material,
+        ))
+    }
+
+    fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        Some(AABB::new(self.center - r, self.center + r))
+    }
+}
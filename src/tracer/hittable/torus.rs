@@ -0,0 +1,87 @@
+use super::{HitRecord, Hittable, AABB};
+use crate::tracer::{
+    material::MaterialEnum,
+    ray::Ray,
+    roots::roots_between,
+    vec3::{dot, unit_vector, Vec3},
+};
+use std::f64::consts::PI;
+
+/// Ring around the y axis at the origin whose tube, of radius `minor`, goes around at the
+/// distance `major` from the axis.
+pub struct Torus {
+    major: f64,
+    minor: f64,
+    material: MaterialEnum,
+}
+
+impl Torus {
+    pub fn new(major: f64, minor: f64, material: MaterialEnum) -> Self {
+        Torus {
+            major,
+            minor,
+            material,
+        }
+    }
+
+    fn bounds(&self) -> AABB {
+        let (outer, minor) = (self.major + self.minor, self.minor);
+        AABB::new(
+            Vec3::new(-outer, -minor, -outer),
+            Vec3::new(outer, minor, outer),
+        )
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let (t_near, t_far) = self.bounds().range(ray, t_min, t_max)?;
+        // solving from where the ray enters the bounds, along a unit direction, keeps the
+        // coefficients of the quartic in proportion to the size of the torus
+        let length = ray.direction().length();
+        let d = ray.direction() / length;
+        let o = ray.at(t_near);
+        let (major2, minor2) = (self.major * self.major, self.minor * self.minor);
+        // (|p|² + R² - r²)² = 4R²(px² + pz²) for p = o + s d, with |d| = 1
+        let k0 = o.length_squared() + major2 - minor2;
+        let k1 = 2.0 * dot(&o, &d);
+        let xz0 = o.x() * o.x() + o.z() * o.z();
+        let xz1 = 2.0 * (o.x() * d.x() + o.z() * d.z());
+        let xz2 = d.x() * d.x() + d.z() * d.z();
+        let coefficients = [
+            k0 * k0 - 4.0 * major2 * xz0,
+            2.0 * k0 * k1 - 4.0 * major2 * xz1,
+            k1 * k1 + 2.0 * k0 - 4.0 * major2 * xz2,
+            2.0 * k1,
+            1.0,
+        ];
+        let span = (t_far - t_near) * length;
+        let t = roots_between(&coefficients, 0.0, span)
+            .into_iter()
+            .map(|s| t_near + s / length)
+            .find(|t| t_min < *t && *t < t_max)?;
+
+        let p = ray.at(t);
+        let ring = p.length_squared() + major2 - minor2;
+        let gradient = Vec3::new(
+            p.x() * (ring - 2.0 * major2),
+            p.y() * ring,
+            p.z() * (ring - 2.0 * major2),
+        );
+        // the angle around the y axis, and the angle around the tube from its outside
+        let u = (p.z().atan2(p.x()) + PI) / (2.0 * PI);
+        let distance = (p.x() * p.x() + p.z() * p.z()).sqrt() - self.major;
+        let v = (p.y().atan2(distance) + PI) / (2.0 * PI);
+        Some(HitRecord::new(
+            ray,
+            t,
+            unit_vector(&gradient),
+            (u, v),
+            &self.material,
+        ))
+    }
+
+    fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+        Some(self.bounds())
+    }
+}
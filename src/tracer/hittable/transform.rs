@@ -0,0 +1,85 @@
+use super::{HitRecord, Hittable, HittableEnum, AABB};
+use crate::tracer::{
+    ray::Ray,
+    vec3::{unit_vector, Point3, Vec3},
+};
+
+/// `obj` under an affine transform, which moves a point p to `linear` p + `offset`.
+pub struct Transform {
+    obj: HittableEnum,
+    linear: [[f64; 3]; 3],
+    offset: Vec3,
+    inverse: [[f64; 3]; 3],
+}
+
+impl Transform {
+    /// `rows` are the top 3 rows of the 4×4 matrix of the transform, which must be invertible.
+    pub fn new(obj: HittableEnum, rows: [[f64; 4]; 3]) -> Self {
+        let linear = rows.map(|row| [row[0], row[1], row[2]]);
+        let offset = Vec3::new(rows[0][3], rows[1][3], rows[2][3]);
+        Transform {
+            obj,
+            linear,
+            offset,
+            inverse: inverse(&linear),
+        }
+    }
+}
+
+fn apply(m: &[[f64; 3]; 3], v: &Vec3) -> Vec3 {
+    let row = |r: [f64; 3]| r[0] * v.x() + r[1] * v.y() + r[2] * v.z();
+    Vec3::new(row(m[0]), row(m[1]), row(m[2]))
+}
+
+fn apply_transposed(m: &[[f64; 3]; 3], v: &Vec3) -> Vec3 {
+    let column = |c: usize| m[0][c] * v.x() + m[1][c] * v.y() + m[2][c] * v.z();
+    Vec3::new(column(0), column(1), column(2))
+}
+
+/// Inverse of `m` from its cofactors.
+fn inverse(m: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let cofactor = |r: usize, c: usize| {
+        let (r0, r1) = ((r + 1) % 3, (r + 2) % 3);
+        let (c0, c1) = ((c + 1) % 3, (c + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let determinant = (0..3).map(|c| m[0][c] * cofactor(0, c)).sum::<f64>();
+    let mut inverse = [[0.0; 3]; 3];
+    for (r, row) in inverse.iter_mut().enumerate() {
+        for (c, entry) in row.iter_mut().enumerate() {
+            *entry = cofactor(c, r) / determinant;
+        }
+    }
+    inverse
+}
+
+impl Hittable for Transform {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // the ray in the object's own space keeps the same t for the same points
+        let local = Ray::new(
+            apply(&self.inverse, &(ray.origin() - self.offset)),
+            apply(&self.inverse, &ray.direction()),
+            ray.time(),
+        );
+        let mut rec = self.obj.hit(&local, t_min, t_max)?;
+        rec.p = ray.at(rec.t);
+        // normals go through the inverse transpose to stay perpendicular to the surface; the
+        // side the ray is on stays the same, so `front_face` does too
+        rec.normal = unit_vector(&apply_transposed(&self.inverse, &rec.normal));
+        Some(rec)
+    }
+
+    fn bounding_box(&self, t0: f64, t1: f64) -> Option<AABB> {
+        let bbox = self.obj.bounding_box(t0, t1)?;
+        let corners = bbox
+            .corners()
+            .map(|corner| apply(&self.linear, &corner) + self.offset);
+        let (minimum, maximum) = corners
+            .iter()
+            .skip(1)
+            .fold((corners[0], corners[0]), |(lo, hi): (Point3, Point3), c| {
+                (lo.min(c), hi.max(c))
+            });
+        Some(AABB::new(minimum, maximum))
+    }
+}
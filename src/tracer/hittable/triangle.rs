@@ -0,0 +1,93 @@
+use super::{padded, HitRecord, Hittable, AABB};
+use crate::tracer::{
+    material::MaterialEnum,
+    ray::Ray,
+    vec3::{cross, dot, unit_vector, Point3, Vec3},
+};
+
+/// Triangle facing (`b` - `a`) × (`c` - `a`). Without texture coordinates, the coordinates of
+/// a hit are its barycentric weights of `b` and `c`.
+pub struct Triangle {
+    vertices: [Point3; 3],
+    /// Normal of each vertex, blended across the triangle to shade it smoothly.
+    normals: Option<[Vec3; 3]>,
+    uvs: Option<[(f64, f64); 3]>,
+    material: MaterialEnum,
+}
+
+impl Triangle {
+    pub fn new(a: &Point3, b: &Point3, c: &Point3, material: MaterialEnum) -> Self {
+        Triangle {
+            vertices: [*a, *b, *c],
+            normals: None,
+            uvs: None,
+            material,
+        }
+    }
+
+    pub fn with_normals(self, normals: [Vec3; 3]) -> Self {
+        Triangle {
+            normals: Some(normals),
+            ..self
+        }
+    }
+
+    pub fn with_uvs(self, uvs: [(f64, f64); 3]) -> Self {
+        Triangle {
+            uvs: Some(uvs),
+            ..self
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // Möller–Trumbore
+        let [a, b, c] = self.vertices;
+        let (edge1, edge2) = (b - a, c - a);
+        let p = cross(&ray.direction(), &edge2);
+        let determinant = dot(&edge1, &p);
+        if determinant.abs() < 1e-12 {
+            return None;
+        }
+        let inverse = 1.0 / determinant;
+        let s = ray.origin() - a;
+        let w1 = dot(&s, &p) * inverse;
+        if !(0.0..=1.0).contains(&w1) {
+            return None;
+        }
+        let q = cross(&s, &edge1);
+        let w2 = dot(&ray.direction(), &q) * inverse;
+        if w2 < 0.0 || w1 + w2 > 1.0 {
+            return None;
+        }
+        let t = dot(&edge2, &q) * inverse;
+        if !(t_min < t && t < t_max) {
+            return None;
+        }
+        let w0 = 1.0 - w1 - w2;
+        let uv = match self.uvs {
+            Some([uv0, uv1, uv2]) => (
+                w0 * uv0.0 + w1 * uv1.0 + w2 * uv2.0,
+                w0 * uv0.1 + w1 * uv1.1 + w2 * uv2.1,
+            ),
+            None => (w1, w2),
+        };
+        let outward_normal = unit_vector(&cross(&edge1, &edge2));
+        let mut rec = HitRecord::new(ray, t, outward_normal, uv, &self.material);
+        if let Some([n0, n1, n2]) = self.normals {
+            // the side the ray is on still comes from the flat normal
+            let shading = n0 * w0 + n1 * w1 + n2 * w2;
+            if !shading.near_zero() {
+                let shading = unit_vector(&shading);
+                rec.normal = if rec.front_face { shading } else { -shading };
+            }
+        }
+        Some(rec)
+    }
+
+    fn bounding_box(&self, _t0: f64, _t1: f64) -> Option<AABB> {
+        let [a, b, c] = self.vertices;
+        Some(padded(a.min(&b).min(&c), a.max(&b).max(&c)))
+    }
+}
@@ -0,0 +1,196 @@
+use super::{
+    hittable::HitRecord,
+    ray::Ray,
+    texture::{Texture, TextureEnum},
+    vec3::{dot, random_in_unit_sphere, random_unit_vector, reflect, refract, unit_vector},
+    vec3::{Color, Point3},
+};
+use rand::Rng;
+use std::sync::Arc;
+
+pub trait Material {
+    /// Attenuation and direction of the light that `ray_in` picks up at `rec`, or `None` if
+    /// the ray is absorbed.
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Color, Ray)>;
+
+    /// Light given off at the surface coordinates `u` and `v` of the point `p`.
+    fn emitted(&self, _u: f64, _v: f64, _p: &Point3) -> Color {
+        Color::zero()
+    }
+}
+
+/// Matte surface scattering light in every direction.
+#[derive(Clone)]
+pub struct Lambertian {
+    albedo: Arc<TextureEnum>,
+}
+
+impl Lambertian {
+    pub fn new(albedo: &TextureEnum) -> Self {
+        Lambertian {
+            albedo: Arc::new(albedo.clone()),
+        }
+    }
+}
+
+impl Material for Lambertian {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Color, Ray)> {
+        let mut direction = rec.normal + random_unit_vector();
+        // the random vector can cancel the normal out
+        if direction.near_zero() {
+            direction = rec.normal;
+        }
+        Some((
+            self.albedo.value(rec.u, rec.v, &rec.p),
+            Ray::new(rec.p, direction, ray_in.time()),
+        ))
+    }
+}
+
+/// Mirror whose reflections blur as `fuzz` goes from 0 to 1.
+#[derive(Clone)]
+pub struct Metal {
+    albedo: Color,
+    fuzz: f64,
+}
+
+impl Metal {
+    pub fn new(albedo: &Color, fuzz: f64) -> Self {
+        Metal {
+            albedo: *albedo,
+            fuzz: fuzz.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Material for Metal {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Color, Ray)> {
+        let reflected = reflect(&unit_vector(&ray_in.direction()), &rec.normal);
+        let scattered = Ray::new(
+            rec.p,
+            reflected + random_in_unit_sphere() * self.fuzz,
+            ray_in.time(),
+        );
+        // fuzz can push the reflection below the surface, where it is absorbed
+        (dot(&scattered.direction(), &rec.normal) > 0.0).then_some((self.albedo, scattered))
+    }
+}
+
+/// Clear material such as glass, refracting light by the refractive index `ir`.
+#[derive(Clone)]
+pub struct Dielectric {
+    ir: f64,
+}
+
+impl Dielectric {
+    pub fn new(ir: f64) -> Self {
+        Dielectric { ir }
+    }
+
+    /// Schlick's approximation of the share of light reflected at an angle whose cosine is
+    /// `cosine`.
+    fn reflectance(cosine: f64, ratio: f64) -> f64 {
+        let r0 = ((1.0 - ratio) / (1.0 + ratio)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
+    }
+}
+
+impl Material for Dielectric {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Color, Ray)> {
+        let ratio = if rec.front_face {
+            1.0 / self.ir
+        } else {
+            self.ir
+        };
+        let direction = unit_vector(&ray_in.direction());
+        let cos_theta = dot(&-direction, &rec.normal).min(1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let scattered = if ratio * sin_theta > 1.0
+            || Self::reflectance(cos_theta, ratio) > rand::thread_rng().gen::<f64>()
+        {
+            reflect(&direction, &rec.normal)
+        } else {
+            refract(&direction, &rec.normal, ratio)
+        };
+        Some((
+            Color::new(1.0, 1.0, 1.0),
+            Ray::new(rec.p, scattered, ray_in.time()),
+        ))
+    }
+}
+
+/// Light source that does not reflect light.
+#[derive(Clone)]
+pub struct DiffuseLight {
+    emit: Arc<TextureEnum>,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: &TextureEnum) -> Self {
+        DiffuseLight {
+            emit: Arc::new(emit.clone()),
+        }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _ray_in: &Ray, _rec: &HitRecord) -> Option<(Color, Ray)> {
+        None
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.emit.value(u, v, p)
+    }
+}
+
+/// Scattering in a medium, which sends light in every direction alike.
+#[derive(Clone)]
+pub struct Isotropic {
+    albedo: Color,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color) -> Self {
+        Isotropic { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Color, Ray)> {
+        Some((
+            self.albedo,
+            Ray::new(rec.p, random_unit_vector(), ray_in.time()),
+        ))
+    }
+}
+
+#[derive(Clone)]
+pub enum MaterialEnum {
+    Lambertian(Lambertian),
+    Metal(Metal),
+    Dielectric(Dielectric),
+    DiffuseLight(DiffuseLight),
+    Isotropic(Isotropic),
+}
+
+impl Material for MaterialEnum {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Color, Ray)> {
+        match self {
+            MaterialEnum::Lambertian(m) => m.scatter(ray_in, rec),
+            MaterialEnum::Metal(m) => m.scatter(ray_in, rec),
+            MaterialEnum::Dielectric(m) => m.scatter(ray_in, rec),
+            MaterialEnum::DiffuseLight(m) => m.scatter(ray_in, rec),
+            MaterialEnum::Isotropic(m) => m.scatter(ray_in, rec),
+        }
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: &Point3) -> Color {
+        match self {
+            MaterialEnum::Lambertian(m) => m.emitted(u, v, p),
+            MaterialEnum::Metal(m) => m.emitted(u, v, p),
+            MaterialEnum::Dielectric(m) => m.emitted(u, v, p),
+            MaterialEnum::DiffuseLight(m) => m.emitted(u, v, p),
+            MaterialEnum::Isotropic(m) => m.emitted(u, v, p),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use super::vec3::{dot, unit_vector, Point3, Vec3};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::sync::OnceLock;
+
+const POINT_COUNT: usize = 256;
+/// Seed of the gradients, fixed so that the noise is the same in every frame and every run.
+const SEED: u64 = 0x6b6f_7672_6179;
+
+/// Gradient noise that varies smoothly from -1 to 1 with features about 1 apart.
+pub(super) struct Perlin {
+    gradients: Vec<Vec3>,
+    permutations: [Vec<usize>; 3],
+}
+
+impl Perlin {
+    /// The noise shared by every texture and medium.
+    pub(super) fn shared() -> &'static Perlin {
+        static PERLIN: OnceLock<Perlin> = OnceLock::new();
+        PERLIN.get_or_init(Perlin::new)
+    }
+
+    fn new() -> Self {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let gradients = (0..POINT_COUNT)
+            .map(|_| {
+                let v = Vec3::new(
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                );
+                unit_vector(&v)
+            })
+            .collect();
+        let mut permutation = || {
+            let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+            p.shuffle(&mut rng);
+            p
+        };
+        let permutations = [permutation(), permutation(), permutation()];
+        Perlin {
+            gradients,
+            permutations,
+        }
+    }
+
+    pub(super) fn noise(&self, p: &Point3) -> f64 {
+        let floor = [p.x().floor(), p.y().floor(), p.z().floor()];
+        let [u, v, w] = [p.x() - floor[0], p.y() - floor[1], p.z() - floor[2]];
+        let [i, j, k] = floor.map(|f| f as i64);
+        // Hermite smoothing hides the grid
+        let [uu, vv, ww] = [u, v, w].map(|t| t * t * (3.0 - 2.0 * t));
+        let mut accum = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let index = self.permutations[0][((i + di) & 255) as usize]
+                        ^ self.permutations[1][((j + dj) & 255) as usize]
+                        ^ self.permutations[2][((k + dk) & 255) as usize];
+                    let (fi, fj, fk) = (di as f64, dj as f64, dk as f64);
+                    let weight = Vec3::new(u - fi, v - fj, w - fk);
+                    accum += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * dot(&self.gradients[index], &weight);
+                }
+            }
+        }
+        accum
+    }
+
+    /// Sum of `depth` octaves of noise, each at twice the frequency and half the weight of the
+    /// one before, as a positive number.
+    pub(super) fn turbulence(&self, p: &Point3, depth: usize) -> f64 {
+        let mut accum = 0.0;
+        let mut p = *p;
+        let mut weight = 1.0;
+        for _ in 0..depth {
+            accum += weight * self.noise(&p);
+            weight *= 0.5;
+            p = p * 2.0;
+        }
+        accum.abs()
+    }
+}
@@ -0,0 +1,61 @@
+use super::{
+    hittable::{Hittable, HittableEnum},
+    material::Material,
+    vec3::{Color, Point3, Vec3},
+};
+
+/// Smallest distance a ray travels before it can hit something, so that a ray leaving a
+/// surface does not hit that surface again through rounding.
+const T_MIN: f64 = 0.001;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ray {
+    origin: Point3,
+    direction: Vec3,
+    time: f64,
+}
+
+impl Ray {
+    pub fn new(origin: Point3, direction: Vec3, time: f64) -> Self {
+        Ray {
+            origin,
+            direction,
+            time,
+        }
+    }
+
+    pub fn origin(&self) -> Point3 {
+        self.origin
+    }
+
+    pub fn direction(&self) -> Vec3 {
+        self.direction
+    }
+
+    /// Time within the shutter interval at which the ray is cast, for moving objects.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn at(&self, t: f64) -> Point3 {
+        self.origin + self.direction * t
+    }
+
+    /// Light coming back along the ray from `world`, following up to `depth` bounces.
+    /// Rays that hit nothing see `background`.
+    pub fn color(&self, background: &Color, world: &HittableEnum, depth: usize) -> Color {
+        if depth == 0 {
+            return Color::zero();
+        }
+        let Some(rec) = world.hit(self, T_MIN, f64::INFINITY) else {
+            return *background;
+        };
+        let emitted = rec.material.emitted(rec.u, rec.v, &rec.p);
+        match rec.material.scatter(self, &rec) {
+            Some((attenuation, scattered)) => {
+                emitted + attenuation.mul_elements(&scattered.color(background, world, depth - 1))
+            }
+            None => emitted,
+        }
+    }
+}
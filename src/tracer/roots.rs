@@ -0,0 +1,106 @@
+/// Real roots of `a t² + b t + c`, in increasing order.
+pub(super) fn quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a == 0.0 {
+        return if b == 0.0 { vec![] } else { vec![-c / b] };
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return vec![];
+    }
+    // the root with the larger magnitude first, without cancellation between b and the square
+    // root, and the other from the product of the roots being c / a
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0.0 {
+        return vec![0.0];
+    }
+    let (r0, r1) = (q / a, c / q);
+    if r0 < r1 {
+        vec![r0, r1]
+    } else {
+        vec![r1, r0]
+    }
+}
+
+/// Roots of the polynomial with `coefficients` from the constant term up, in increasing order
+/// from `lo` to `hi`. Each root is isolated between the roots of the derivative, where the
+/// polynomial is monotonic, and then found by bisection, so roots where the polynomial only
+/// touches 0 without crossing it can be missed.
+pub(super) fn roots_between(coefficients: &[f64], lo: f64, hi: f64) -> Vec<f64> {
+    let degree = match coefficients.iter().rposition(|c| *c != 0.0) {
+        Some(degree) => degree,
+        None => return vec![],
+    };
+    let coefficients = &coefficients[..=degree];
+    if degree == 0 {
+        return vec![];
+    }
+    if degree == 1 {
+        let root = -coefficients[0] / coefficients[1];
+        return if lo <= root && root <= hi {
+            vec![root]
+        } else {
+            vec![]
+        };
+    }
+    let derivative: Vec<f64> = coefficients
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| i as f64 * c)
+        .collect();
+    let mut ends = vec![lo];
+    ends.extend(roots_between(&derivative, lo, hi));
+    ends.push(hi);
+    let mut roots = vec![];
+    for pair in ends.windows(2) {
+        if let Some(root) = bisect(coefficients, pair[0], pair[1]) {
+            // a root at the end of one interval is also at the start of the next
+            if roots
+                .last()
+                .is_none_or(|last: &f64| root - last > 1e-12 * (1.0 + root.abs()))
+            {
+                roots.push(root);
+            }
+        }
+    }
+    roots
+}
+
+/// Value of the polynomial with `coefficients` at `t`.
+pub(super) fn evaluate(coefficients: &[f64], t: f64) -> f64 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0.0, |value, c| value * t + c)
+}
+
+/// A root between `lo` and `hi` of a polynomial that is monotonic between them.
+fn bisect(coefficients: &[f64], mut lo: f64, mut hi: f64) -> Option<f64> {
+    let f_lo = evaluate(coefficients, lo);
+    let f_hi = evaluate(coefficients, hi);
+    if f_lo == 0.0 {
+        return Some(lo);
+    }
+    if f_hi == 0.0 {
+        return Some(hi);
+    }
+    if f_lo.signum() == f_hi.signum() {
+        return None;
+    }
+    for _ in 0..100 {
+        let mid = 0.5 * (lo + hi);
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        let f_mid = evaluate(coefficients, mid);
+        if f_mid == 0.0 {
+            return Some(mid);
+        }
+        if f_mid.signum() == f_lo.signum() {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some(0.5 * (lo + hi))
+}
@@ -0,0 +1,150 @@
+use super::{
+    perlin::Perlin,
+    vec3::{Color, Point3},
+};
+use std::sync::Arc;
+
+pub trait Texture {
+    /// Color at the surface coordinates `u` and `v` of the point `p`.
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color;
+}
+
+#[derive(Clone)]
+pub struct SolidColor {
+    color: Color,
+}
+
+impl SolidColor {
+    pub fn new(color: Color) -> Self {
+        SolidColor { color }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f64, _v: f64, _p: &Point3) -> Color {
+        self.color
+    }
+}
+
+/// Two textures alternating in a 3D checkerboard of cells about 0.3 wide.
+#[derive(Clone)]
+pub struct Checker {
+    odd: Arc<TextureEnum>,
+    even: Arc<TextureEnum>,
+}
+
+impl Checker {
+    pub fn new(odd: TextureEnum, even: TextureEnum) -> Self {
+        Checker {
+            odd: Arc::new(odd),
+            even: Arc::new(even),
+        }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        let sines = (10.0 * p.x()).sin() * (10.0 * p.y()).sin() * (10.0 * p.z()).sin();
+        if sines < 0.0 {
+            self.odd.value(u, v, p)
+        } else {
+            self.even.value(u, v, p)
+        }
+    }
+}
+
+/// Gray marble whose veins run along z, `scale` times as close together as with a scale of 1.
+#[derive(Clone)]
+pub struct NoiseTexture {
+    scale: f64,
+}
+
+impl NoiseTexture {
+    pub fn new(scale: f64) -> Self {
+        NoiseTexture { scale }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, p: &Point3) -> Color {
+        let turbulence = Perlin::shared().turbulence(p, 7);
+        Color::new(1.0, 1.0, 1.0) * 0.5 * (1.0 + (self.scale * p.z() + 10.0 * turbulence).sin())
+    }
+}
+
+/// An RGB image with `u` running left to right and `v` bottom to top. The pixels are shared
+/// between clones, so that every triangle of a textured model can hold the texture.
+#[derive(Clone)]
+pub struct ImageTexture {
+    width: u32,
+    height: u32,
+    pixels: Arc<[u8]>,
+}
+
+impl ImageTexture {
+    /// `pixels` holds 3 bytes per pixel, row by row from the top.
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        ImageTexture {
+            width,
+            height,
+            pixels: pixels.into(),
+        }
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _p: &Point3) -> Color {
+        if self.width == 0 || self.height == 0 {
+            return Color::new(0.0, 1.0, 1.0);
+        }
+        // texture coordinates outside 0 to 1 repeat the image
+        let u = u - u.floor();
+        let v = 1.0 - (v - v.floor());
+        let i = ((u * self.width as f64) as u32).min(self.width - 1);
+        let j = ((v * self.height as f64) as u32).min(self.height - 1);
+        let start = 3 * (j as usize * self.width as usize + i as usize);
+        let pixel = &self.pixels[start..start + 3];
+        Color::new(pixel[0] as f64, pixel[1] as f64, pixel[2] as f64) / 255.0
+    }
+}
+
+/// Colors of the corners of a triangle, blended across it. The triangle's texture coordinates
+/// must be the barycentric weights of its second and third corners.
+#[derive(Clone)]
+pub struct VertexColors {
+    colors: [Color; 3],
+}
+
+impl VertexColors {
+    pub fn new(colors: [Color; 3]) -> Self {
+        VertexColors { colors }
+    }
+}
+
+impl Texture for VertexColors {
+    fn value(&self, u: f64, v: f64, _p: &Point3) -> Color {
+        let [a, b, c] = self.colors;
+        a * (1.0 - u - v) + b * u + c * v
+    }
+}
+
+#[derive(Clone)]
+pub enum TextureEnum {
+    SolidColor(SolidColor),
+    Checker(Checker),
+    NoiseTexture(NoiseTexture),
+    ImageTexture(ImageTexture),
+    VertexColors(VertexColors),
+}
+
+impl Texture for TextureEnum {
+    fn value(&self, u: f64, v: f64, p: &Point3) -> Color {
+        match self {
+            TextureEnum::SolidColor(t) => t.value(u, v, p),
+            TextureEnum::Checker(t) => t.value(u, v, p),
+            TextureEnum::NoiseTexture(t) => t.value(u, v, p),
+            TextureEnum::ImageTexture(t) => t.value(u, v, p),
+            TextureEnum::VertexColors(t) => t.value(u, v, p),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, AddAssign, Div, Index, Mul, Neg, Sub};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vec3 {
+    e: [f64; 3],
+}
+
+pub type Point3 = Vec3;
+/// A color with each of red, green and blue from 0 to 1, or above 1 for lights.
+pub type Color = Vec3;
+
+impl Vec3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Vec3 { e: [x, y, z] }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(0.0, 0.0, 0.0)
+    }
+
+    pub fn x(&self) -> f64 {
+        self.e[0]
+    }
+
+    pub fn y(&self) -> f64 {
+        self.e[1]
+    }
+
+    pub fn z(&self) -> f64 {
+        self.e[2]
+    }
+
+    pub fn length(&self) -> f64 {
+        self.length_squared().sqrt()
+    }
+
+    pub fn length_squared(&self) -> f64 {
+        dot(self, self)
+    }
+
+    /// Whether the vector is so close to zero in every axis that it has no direction.
+    pub fn near_zero(&self) -> bool {
+        self.e.iter().all(|e| e.abs() < 1e-8)
+    }
+
+    /// Each component of `self` and `other` multiplied together.
+    pub fn mul_elements(&self, other: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.x() * other.x(),
+            self.y() * other.y(),
+            self.z() * other.z(),
+        )
+    }
+
+    /// Smaller of the components of `self` and `other` in each axis.
+    pub fn min(&self, other: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.x().min(other.x()),
+            self.y().min(other.y()),
+            self.z().min(other.z()),
+        )
+    }
+
+    /// Larger of the components of `self` and `other` in each axis.
+    pub fn max(&self, other: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.x().max(other.x()),
+            self.y().max(other.y()),
+            self.z().max(other.z()),
+        )
+    }
+
+    /// The 8-bit RGB of the sum of `samples_per_pixel` samples, gamma corrected for a gamma
+    /// of 2. Samples that went wrong, e.g. to NaN, count as black.
+    pub fn get_color(&self, samples_per_pixel: i64) -> (u8, u8, u8) {
+        let scale = 1.0 / samples_per_pixel.max(1) as f64;
+        let channel = |c: f64| {
+            let c = if c.is_nan() { 0.0 } else { (c * scale).sqrt() };
+            (256.0 * c.clamp(0.0, 0.999)) as u8
+        };
+        (channel(self.x()), channel(self.y()), channel(self.z()))
+    }
+
+    pub fn random(min: f64, max: f64) -> Vec3 {
+        let mut rng = rand::thread_rng();
+        Vec3::new(
+            rng.gen_range(min..max),
+            rng.gen_range(min..max),
+            rng.gen_range(min..max),
+        )
+    }
+}
+
+pub fn dot(a: &Vec3, b: &Vec3) -> f64 {
+    a.x() * b.x() + a.y() * b.y() + a.z() * b.z()
+}
+
+pub fn cross(a: &Vec3, b: &Vec3) -> Vec3 {
+    Vec3::new(
+        a.y() * b.z() - a.z() * b.y(),
+        a.z() * b.x() - a.x() * b.z(),
+        a.x() * b.y() - a.y() * b.x(),
+    )
+}
+
+pub fn unit_vector(v: &Vec3) -> Vec3 {
+    *v / v.length()
+}
+
+/// `v` mirrored about the plane with normal `n`.
+pub fn reflect(v: &Vec3, n: &Vec3) -> Vec3 {
+    *v - *n * (2.0 * dot(v, n))
+}
+
+/// The unit vector `uv` bent through a surface with normal `n`, going from a medium with
+/// refractive index `etai_over_etat` times that of the other.
+pub fn refract(uv: &Vec3, n: &Vec3, etai_over_etat: f64) -> Vec3 {
+    let cos_theta = dot(&-*uv, n).min(1.0);
+    let r_out_perp = (*uv + *n * cos_theta) * etai_over_etat;
+    let r_out_parallel = *n * -(1.0 - r_out_perp.length_squared()).abs().sqrt();
+    r_out_perp + r_out_parallel
+}
+
+pub fn random_in_unit_sphere() -> Vec3 {
+    loop {
+        let p = Vec3::random(-1.0, 1.0);
+        if p.length_squared() < 1.0 {
+            return p;
+        }
+    }
+}
+
+pub fn random_unit_vector() -> Vec3 {
+    unit_vector(&random_in_unit_sphere())
+}
+
+pub fn random_in_unit_disk() -> Vec3 {
+    let mut rng = rand::thread_rng();
+    loop {
+        let p = Vec3::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), 0.0);
+        if p.length_squared() < 1.0 {
+            return p;
+        }
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self.x() + other.x(),
+            self.y() + other.y(),
+            self.z() + other.z(),
+        )
+    }
+}
+
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, other: Vec3) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self.x() - other.x(),
+            self.y() - other.y(),
+            self.z() - other.z(),
+        )
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x(), -self.y(), -self.z())
+    }
+}
+
+impl Mul<f64> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, t: f64) -> Vec3 {
+        Vec3::new(self.x() * t, self.y() * t, self.z() * t)
+    }
+}
+
+impl Mul<Vec3> for f64 {
+    type Output = Vec3;
+
+    fn mul(self, v: Vec3) -> Vec3 {
+        v * self
+    }
+}
+
+impl Div<f64> for Vec3 {
+    type Output = Vec3;
+
+    fn div(self, t: f64) -> Vec3 {
+        self * (1.0 / t)
+    }
+}
+
+impl Index<usize> for Vec3 {
+    type Output = f64;
+
+    fn index(&self, i: usize) -> &f64 {
+        &self.e[i]
+    }
+}